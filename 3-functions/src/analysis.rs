@@ -0,0 +1,231 @@
+//! Frequency analysis of the letters of a text, to show why classical ciphers are breakable.
+
+use std::collections::HashMap;
+
+use crate::ciphers::get_base_code_option;
+use crate::cryptanalysis::{
+    count_letters, get_letter_positions, index_of_coincidence, ENGLISH_FREQUENCIES,
+};
+
+/// The number of bigrams shown in the text report.
+const TOP_BIGRAMS: usize = 20;
+/// The width of the bar of the most frequent letter in the text report.
+const BAR_WIDTH: usize = 40;
+
+/// A `struct` with the frequency analysis of a text.
+pub struct FrequencyReport {
+    /// The number of times each letter appears, from `a` to `z`.
+    pub letter_counts: [usize; 26],
+    /// The pairs of consecutive letters inside words and their counts, from the most frequent.
+    pub bigram_counts: Vec<(String, usize)>,
+    /// The total number of letters.
+    pub total_letters: usize,
+    /// The index of coincidence of the letters.
+    pub index_of_coincidence: f64,
+    /// The Shannon entropy of the letters, in bits per letter.
+    pub entropy: f64,
+}
+
+/// Analyzes the letters of a text, ignoring the case and any other character.
+/// ### Arguments
+/// * `text` - A string slice with the text to analyze.
+/// ### Returns
+/// * `FrequencyReport` - The letter and bigram counts, the index of coincidence and the entropy.
+pub fn analyze_text(text: &str) -> FrequencyReport {
+    let letter_counts: [usize; 26] = count_letters(&get_letter_positions(text));
+    let total_letters: usize = letter_counts.iter().sum();
+    FrequencyReport {
+        letter_counts,
+        bigram_counts: count_bigrams(text),
+        total_letters,
+        index_of_coincidence: index_of_coincidence(&letter_counts),
+        entropy: get_entropy(&letter_counts),
+    }
+}
+
+/// Counts the pairs of consecutive letters, lowercased, that are inside the same word.
+fn count_bigrams(text: &str) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut previous_letter: Option<char> = None;
+    for the_char in text.chars() {
+        if get_base_code_option(the_char).is_none() {
+            previous_letter = None;
+            continue;
+        }
+        let letter: char = the_char.to_ascii_lowercase();
+        if let Some(previous_letter) = previous_letter {
+            *counts
+                .entry(format!("{}{}", previous_letter, letter))
+                .or_default() += 1;
+        }
+        previous_letter = Some(letter);
+    }
+    let mut bigram_counts: Vec<(String, usize)> = counts.into_iter().collect();
+    bigram_counts.sort_by(|first, second| second.1.cmp(&first.1).then(first.0.cmp(&second.0)));
+    bigram_counts
+}
+
+/// Calculates the Shannon entropy of the letter counts, in bits per letter.
+///
+/// Uniformly random letters have log2(26), close to 4.7 bits; English texts are close to 4.2.
+fn get_entropy(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability: f64 = *count as f64 / total as f64;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+/// Formats a report as a text table, with a bar for every letter.
+/// ### Arguments
+/// * `report` - A `FrequencyReport` to format.
+/// ### Returns
+/// * `String` - The report as text lines.
+pub fn format_text(report: &FrequencyReport) -> String {
+    let max_count: usize = report
+        .letter_counts
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let mut text: String = String::new();
+    text.push_str(&format!("Letters: {}\n", report.total_letters));
+    text.push_str(&format!(
+        "Index of coincidence: {:.4} (English 0.0667, random 0.0385)\n",
+        report.index_of_coincidence
+    ));
+    text.push_str(&format!(
+        "Entropy: {:.4} bits per letter (English 4.18, random 4.70)\n\n",
+        report.entropy
+    ));
+    text.push_str("Letter  Count  Percent  English  Bar\n");
+    for (position, count) in report.letter_counts.iter().enumerate() {
+        let percent: f64 = get_percent(*count, report.total_letters);
+        let bar: String = "#".repeat(count * BAR_WIDTH / max_count);
+        text.push_str(&format!(
+            "{:>6}  {:>5}  {:>6.2}%  {:>6.2}%  {}\n",
+            (b'a' + position as u8) as char,
+            count,
+            percent,
+            ENGLISH_FREQUENCIES[position] * 100.0,
+            bar
+        ));
+    }
+    let total_bigrams: usize = report.bigram_counts.iter().map(|(_, count)| count).sum();
+    text.push_str("\nBigram  Count  Percent\n");
+    for (bigram, count) in report.bigram_counts.iter().take(TOP_BIGRAMS) {
+        text.push_str(&format!(
+            "{:>6}  {:>5}  {:>6.2}%\n",
+            bigram,
+            count,
+            get_percent(*count, total_bigrams)
+        ));
+    }
+    text
+}
+
+/// Formats a report as a JSON object, with all the letters and bigrams.
+/// ### Arguments
+/// * `report` - A `FrequencyReport` to format.
+/// ### Returns
+/// * `String` - The report as JSON.
+pub fn format_json(report: &FrequencyReport) -> String {
+    let letters: Vec<String> = report
+        .letter_counts
+        .iter()
+        .enumerate()
+        .map(|(position, count)| format!("\"{}\": {}", (b'a' + position as u8) as char, count))
+        .collect();
+    let bigrams: Vec<String> = report
+        .bigram_counts
+        .iter()
+        .map(|(bigram, count)| format!("\"{}\": {}", bigram, count))
+        .collect();
+    format!(
+        "{{\n  \"total_letters\": {},\n  \"index_of_coincidence\": {:.6},\n  \"entropy\": {:.6},\n  \"letters\": {{ {} }},\n  \"bigrams\": {{ {} }}\n}}\n",
+        report.total_letters,
+        report.index_of_coincidence,
+        report.entropy,
+        letters.join(", "),
+        bigrams.join(", ")
+    )
+}
+
+/// Gets the percent of a count over a total, being 0 for an empty total.
+fn get_percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    count as f64 * 100.0 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_and_bigrams_are_counted_inside_words() {
+        let report: FrequencyReport = analyze_text("Aab, ba! ñ 7");
+        assert_eq!(report.total_letters, 5);
+        assert_eq!(report.letter_counts[0], 3);
+        assert_eq!(report.letter_counts[1], 2);
+        assert_eq!(
+            report.bigram_counts,
+            vec![
+                ("aa".to_string(), 1),
+                ("ab".to_string(), 1),
+                ("ba".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn coincidence_and_entropy_match_known_distributions() {
+        let uniform: FrequencyReport = analyze_text("abcdefghijklmnopqrstuvwxyz");
+        assert!((uniform.entropy - 26f64.log2()).abs() < 1e-9);
+        assert_eq!(uniform.index_of_coincidence, 0.0);
+        let repeated: FrequencyReport = analyze_text("zzzz");
+        assert_eq!(repeated.entropy, 0.0);
+        assert_eq!(repeated.index_of_coincidence, 1.0);
+        let halves: FrequencyReport = analyze_text("abab");
+        assert!((halves.entropy - 1.0).abs() < 1e-9);
+        assert!((halves.index_of_coincidence - 1.0 / 3.0).abs() < 1e-9);
+        let empty: FrequencyReport = analyze_text("123 ¡!");
+        assert_eq!(empty.total_letters, 0);
+        assert_eq!(empty.entropy, 0.0);
+        assert_eq!(empty.index_of_coincidence, 0.0);
+    }
+
+    #[test]
+    fn json_has_the_totals_and_every_letter() {
+        let json: String = format_json(&analyze_text("abab"));
+        assert!(json.starts_with("{\n  \"total_letters\": 4,\n"));
+        assert!(json.contains("\"index_of_coincidence\": 0.333333,"));
+        assert!(json.contains("\"entropy\": 1.000000,"));
+        assert!(json.contains("\"letters\": { \"a\": 2, \"b\": 2, \"c\": 0,"));
+        assert!(json.contains("\"z\": 0 }"));
+        assert!(json.contains("\"bigrams\": { \"ab\": 2, \"ba\": 1 }"));
+        assert!(json.ends_with("}\n"));
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+        let empty_json: String = format_json(&analyze_text(""));
+        assert!(empty_json.contains("\"bigrams\": {  }"));
+    }
+
+    #[test]
+    fn text_report_has_a_row_for_every_letter() {
+        let text: String = format_text(&analyze_text("abab"));
+        assert!(text.starts_with("Letters: 4\n"));
+        assert!(text.contains("Index of coincidence: 0.3333"));
+        assert!(text.contains(&format!("{:>6}  {:>5}  {:>6.2}%", 'a', 2, 50.0)));
+        let letter_rows: usize = text
+            .lines()
+            .filter(|line| line.matches('%').count() == 2)
+            .count();
+        assert_eq!(letter_rows, 26);
+    }
+}
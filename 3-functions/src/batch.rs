@@ -0,0 +1,462 @@
+//! Batch processing of whole directory trees, file by file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A `struct` with the result of processing one file of a directory and how long it took.
+pub struct FileResult {
+    /// The path of the file, relative to the input and output directories.
+    pub relative_path: PathBuf,
+    /// The number of bytes read, or the error that stopped the file.
+    pub outcome: Result<u64, std::io::Error>,
+    /// The time spent on the file.
+    pub duration: Duration,
+}
+
+/// A `struct` that counts the files and bytes done, to estimate the remaining time.
+///
+/// The bytes are the sizes of the input files, known before processing them.
+struct BatchProgress {
+    start_time: Instant,
+    total_files: usize,
+    total_bytes: u64,
+    done_files: usize,
+    done_bytes: u64,
+}
+
+impl BatchProgress {
+    /// Formats a line with the files done, the speed and the estimated time left.
+    fn format_line(&self) -> String {
+        let elapsed_seconds: f64 = self.start_time.elapsed().as_secs_f64();
+        let bytes_per_second: f64 = if elapsed_seconds > 0.0 {
+            self.done_bytes as f64 / elapsed_seconds
+        } else {
+            0.0
+        };
+        let remaining_bytes: u64 = self.total_bytes.saturating_sub(self.done_bytes);
+        let eta: String = if remaining_bytes == 0 {
+            "0.0 s".to_string()
+        } else if bytes_per_second > 0.0 {
+            format!("{:.1} s", remaining_bytes as f64 / bytes_per_second)
+        } else {
+            "?".to_string()
+        };
+        format!(
+            "⏳ {}/{} files, {:.1} KB/s, ETA {}",
+            self.done_files,
+            self.total_files,
+            bytes_per_second / 1024.0,
+            eta
+        )
+    }
+}
+
+/// Finds the files of a directory tree that match the include and exclude patterns.
+///
+/// A pattern with a `/` matches the path relative to the root, otherwise the file name.
+/// The excluded folders are not visited, and neither is the `skipped_folder`,
+/// used to avoid reading an output directory that is inside the input one.
+/// ### Arguments
+/// * `root` - The directory to walk.
+/// * `include_patterns` - The patterns of the files to process; all of them when empty.
+/// * `exclude_patterns` - The patterns of the files and folders to skip.
+/// * `skipped_folder` - A folder never visited.
+/// ### Returns
+/// * `Result<Vec<PathBuf>, std::io::Error>` - The paths relative to the root, sorted,
+///   or an error if a folder can not be read.
+pub fn find_files(
+    root: &Path,
+    include_patterns: &[String],
+    exclude_patterns: &[String],
+    skipped_folder: &Path,
+) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut relative_paths: Vec<PathBuf> = vec![];
+    let mut pending_folders: Vec<PathBuf> = vec![PathBuf::new()];
+    while let Some(relative_folder) = pending_folders.pop() {
+        for entry in fs::read_dir(root.join(&relative_folder))? {
+            let entry: fs::DirEntry = entry?;
+            let relative_path: PathBuf = relative_folder.join(entry.file_name());
+            if matches_any(exclude_patterns, &relative_path) {
+                continue;
+            }
+            let file_type: fs::FileType = entry.file_type()?;
+            if file_type.is_dir() {
+                if !is_same_path(&entry.path(), skipped_folder) {
+                    pending_folders.push(relative_path);
+                }
+            } else if file_type.is_file()
+                && (include_patterns.is_empty() || matches_any(include_patterns, &relative_path))
+            {
+                relative_paths.push(relative_path);
+            }
+        }
+    }
+    relative_paths.sort();
+    Ok(relative_paths)
+}
+
+/// Processes every file with a pool of worker threads, creating the mirrored folders
+/// of the output directory, and keeps going when one of them fails.
+///
+/// Every worker takes the next pending file, so big files do not hold back the rest.
+/// A file whose worker panicked gets an error, so it is never left out of the results.
+/// ### Arguments
+/// * `input_root` - The directory to read.
+/// * `output_root` - The directory to write, with the same tree.
+/// * `relative_paths` - The files to process, relative to both roots.
+/// * `jobs` - The number of worker threads, at least 1.
+/// * `report_progress` - A function called with a progress line after every file,
+///   from several threads at once.
+/// * `process_file` - A function from the input and output paths to the processed bytes,
+///   called from several threads at once.
+/// ### Returns
+/// * `Vec<FileResult>` - The result of every file, in the same order.
+pub fn process_files(
+    input_root: &Path,
+    output_root: &Path,
+    relative_paths: &[PathBuf],
+    jobs: usize,
+    report_progress: &(dyn Fn(&str) + Sync),
+    process_file: impl Fn(&Path, &Path) -> Result<u64, std::io::Error> + Sync,
+) -> Vec<FileResult> {
+    let file_sizes: Vec<u64> = relative_paths
+        .iter()
+        .map(|relative_path| {
+            fs::metadata(input_root.join(relative_path)).map_or(0, |metadata| metadata.len())
+        })
+        .collect();
+    let progress: Mutex<BatchProgress> = Mutex::new(BatchProgress {
+        start_time: Instant::now(),
+        total_files: relative_paths.len(),
+        total_bytes: file_sizes.iter().sum(),
+        done_files: 0,
+        done_bytes: 0,
+    });
+    let next_index: AtomicUsize = AtomicUsize::new(0);
+    let file_results: Mutex<Vec<Option<FileResult>>> =
+        Mutex::new(relative_paths.iter().map(|_| None).collect());
+    let process_next_files = || loop {
+        let index: usize = next_index.fetch_add(1, Ordering::Relaxed);
+        let Some(relative_path) = relative_paths.get(index) else {
+            return;
+        };
+        let file_start: Instant = Instant::now();
+        let output_path: PathBuf = output_root.join(relative_path);
+        let outcome: Result<u64, std::io::Error> = match output_path.parent() {
+            Some(output_folder) => fs::create_dir_all(output_folder),
+            None => Ok(()),
+        }
+        .and_then(|_| process_file(&input_root.join(relative_path), &output_path));
+        let file_result: FileResult = FileResult {
+            relative_path: relative_path.clone(),
+            outcome,
+            duration: file_start.elapsed(),
+        };
+        file_results
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())[index] = Some(file_result);
+        let mut progress = progress.lock().unwrap_or_else(|error| error.into_inner());
+        progress.done_files += 1;
+        progress.done_bytes += file_sizes[index];
+        report_progress(&progress.format_line());
+    };
+    thread::scope(|scope| {
+        let workers: Vec<thread::ScopedJoinHandle<()>> = (0..jobs
+            .clamp(1, relative_paths.len().max(1)))
+            .map(|_| scope.spawn(process_next_files))
+            .collect();
+        for worker in workers {
+            // A panic leaves the files of the worker without result, reported below.
+            let _ = worker.join();
+        }
+    });
+    file_results
+        .into_inner()
+        .unwrap_or_else(|error| error.into_inner())
+        .into_iter()
+        .zip(relative_paths)
+        .map(|(file_result, relative_path)| {
+            file_result.unwrap_or_else(|| FileResult {
+                relative_path: relative_path.clone(),
+                outcome: Err(std::io::Error::other(
+                    "⚠️ - The worker processing this file stopped unexpectedly.",
+                )),
+                duration: Duration::ZERO,
+            })
+        })
+        .collect()
+}
+
+/// Formats a summary with a line for every file, with its timing, and the totals.
+/// ### Arguments
+/// * `results` - A slice of `FileResult` with the outcome of every file.
+/// ### Returns
+/// * `String` - The summary text.
+pub fn format_summary(results: &[FileResult]) -> String {
+    let mut summary: String = String::new();
+    let mut total_bytes: u64 = 0;
+    let mut failed_count: usize = 0;
+    for result in results.iter() {
+        match &result.outcome {
+            Ok(byte_count) => {
+                total_bytes += byte_count;
+                summary.push_str(&format!(
+                    "✅ {} ({} bytes in {} ms)\n",
+                    result.relative_path.display(),
+                    byte_count,
+                    result.duration.as_millis()
+                ));
+            }
+            Err(error) => {
+                failed_count += 1;
+                summary.push_str(&format!(
+                    "❌ {} (after {} ms): {}\n",
+                    result.relative_path.display(),
+                    result.duration.as_millis(),
+                    error
+                ));
+            }
+        }
+    }
+    summary.push_str(&format!(
+        "📊 {} files processed, {} failed, {} bytes read\n",
+        results.len() - failed_count,
+        failed_count,
+        total_bytes
+    ));
+    summary
+}
+
+/// Checks if a path matches a glob pattern.
+///
+/// The `*` matches any text inside a folder name, `**` any text including folders,
+/// and `?` any single char but the separator.
+/// ### Arguments
+/// * `pattern` - A string slice with the pattern, like `*.txt` or `docs/**/*.md`.
+/// * `path` - A string slice with the path, using `/` as separator.
+/// ### Returns
+/// * `bool` - True if the whole path matches.
+/// ### Example
+/// ```
+/// use functions::batch::matches_glob;
+///
+/// assert!(matches_glob("*.txt", "notes.txt"));
+/// assert!(!matches_glob("*.txt", "docs/notes.txt"));
+/// assert!(matches_glob("docs/**/*.md", "docs/guide/intro.md"));
+/// assert!(matches_glob("docs/**/*.md", "docs/readme.md"));
+/// ```
+pub fn matches_glob(pattern: &str, path: &str) -> bool {
+    let pattern_chars: Vec<char> = pattern.chars().collect();
+    let path_chars: Vec<char> = path.chars().collect();
+    matches_glob_chars(&pattern_chars, &path_chars)
+}
+
+/// Matches the chars of a glob pattern, trying every length for the wildcards.
+fn matches_glob_chars(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            matches_glob_chars(rest, path)
+                || (0..path.len())
+                    .any(|index| path[index] == '/' && matches_glob_chars(rest, &path[index + 1..]))
+        }
+        ['*', '*', rest @ ..] => {
+            (0..=path.len()).any(|index| matches_glob_chars(rest, &path[index..]))
+        }
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|index| *index == 0 || path[index - 1] != '/')
+            .any(|index| matches_glob_chars(rest, &path[index..])),
+        ['?', rest @ ..] => {
+            path.first().is_some_and(|the_char| *the_char != '/')
+                && matches_glob_chars(rest, &path[1..])
+        }
+        [pattern_char, rest @ ..] => {
+            path.first() == Some(pattern_char) && matches_glob_chars(rest, &path[1..])
+        }
+    }
+}
+
+/// Checks if a relative path matches any of the patterns.
+fn matches_any(patterns: &[String], relative_path: &Path) -> bool {
+    let path: String = relative_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let file_name: String = relative_path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    patterns.iter().any(|pattern| {
+        if pattern.contains('/') {
+            matches_glob(pattern, &path)
+        } else {
+            matches_glob(pattern, &file_name)
+        }
+    })
+}
+
+/// Checks if two paths point to the same existing file or folder.
+fn is_same_path(first_path: &Path, second_path: &Path) -> bool {
+    match (fs::canonicalize(first_path), fs::canonicalize(second_path)) {
+        (Ok(first_path), Ok(second_path)) => first_path == second_path,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty folder in the temporary directory, unique for the test and the process.
+    fn new_temp_folder(name: &str) -> PathBuf {
+        let folder: PathBuf =
+            std::env::temp_dir().join(format!("rs-lab-batch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    /// Creates a file with some content, and its folders.
+    fn write_file(root: &Path, relative_path: &str, content: &str) {
+        let path: PathBuf = root.join(relative_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Creates a tree with text files, notes and a folder to exclude, at several depths.
+    fn new_sample_tree(name: &str) -> PathBuf {
+        let root: PathBuf = new_temp_folder(name);
+        write_file(&root, "a.txt", "first");
+        write_file(&root, "b.md", "second");
+        write_file(&root, "docs/c.txt", "third");
+        write_file(&root, "docs/deep/d.txt", "fourth");
+        write_file(&root, "target/e.txt", "fifth");
+        root
+    }
+
+    /// Gets a list of patterns from string slices.
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|pattern| pattern.to_string()).collect()
+    }
+
+    #[test]
+    fn find_files_recurses_and_applies_the_patterns() {
+        let root: PathBuf = new_sample_tree("find");
+        let skipped_folder: PathBuf = root.join("target");
+        let find = |include_patterns: &[&str], exclude_patterns: &[&str]| -> Vec<PathBuf> {
+            find_files(
+                &root,
+                &patterns(include_patterns),
+                &patterns(exclude_patterns),
+                &skipped_folder,
+            )
+            .unwrap()
+        };
+        let to_paths =
+            |paths: &[&str]| -> Vec<PathBuf> { paths.iter().map(PathBuf::from).collect() };
+        assert_eq!(
+            find(&[], &[]),
+            to_paths(&["a.txt", "b.md", "docs/c.txt", "docs/deep/d.txt"])
+        );
+        assert_eq!(
+            find(&["*.txt"], &[]),
+            to_paths(&["a.txt", "docs/c.txt", "docs/deep/d.txt"])
+        );
+        assert_eq!(find(&["docs/*.txt"], &[]), to_paths(&["docs/c.txt"]));
+        assert_eq!(
+            find(&[], &["deep", "*.md"]),
+            to_paths(&["a.txt", "docs/c.txt"])
+        );
+        assert_eq!(find(&["*.txt"], &["docs/**/*.txt"]), to_paths(&["a.txt"]));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn names_are_matched_with_the_file_name_of_the_path() {
+        let relative_path: PathBuf = Path::new("docs").join("deep").join("notes.txt");
+        assert!(matches_any(&patterns(&["*.txt"]), &relative_path));
+        assert!(matches_any(&patterns(&["notes.*"]), &relative_path));
+        assert!(!matches_any(&patterns(&["deep"]), &relative_path));
+        assert!(matches_any(&patterns(&["docs/**/*.txt"]), &relative_path));
+    }
+
+    #[test]
+    fn process_files_mirrors_the_tree_and_counts_the_errors() {
+        let input_root: PathBuf = new_sample_tree("process-input");
+        let output_root: PathBuf = new_temp_folder("process-output");
+        let relative_paths: Vec<PathBuf> =
+            find_files(&input_root, &[], &patterns(&["target"]), &output_root).unwrap();
+        let progress_lines: Mutex<Vec<String>> = Mutex::new(vec![]);
+        let results: Vec<FileResult> = process_files(
+            &input_root,
+            &output_root,
+            &relative_paths,
+            3,
+            &|progress_line| {
+                progress_lines
+                    .lock()
+                    .unwrap()
+                    .push(progress_line.to_string())
+            },
+            |input_path, output_path| {
+                let content: String = fs::read_to_string(input_path)?;
+                if content == "second" {
+                    return Err(std::io::Error::other("refused"));
+                }
+                fs::write(output_path, content.to_uppercase())?;
+                Ok(content.len() as u64)
+            },
+        );
+        let result_paths: Vec<&PathBuf> =
+            results.iter().map(|result| &result.relative_path).collect();
+        assert_eq!(result_paths, relative_paths.iter().collect::<Vec<_>>());
+        assert!(results[1].outcome.is_err());
+        assert_eq!(results[2].outcome.as_ref().unwrap(), &5);
+        assert_eq!(
+            fs::read_to_string(output_root.join("docs/deep/d.txt")).unwrap(),
+            "FOURTH"
+        );
+        assert!(!output_root.join("b.md").exists());
+        assert_eq!(progress_lines.lock().unwrap().len(), 4);
+        assert!(
+            format_summary(&results).ends_with("📊 3 files processed, 1 failed, 16 bytes read\n")
+        );
+        fs::remove_dir_all(&input_root).unwrap();
+        fs::remove_dir_all(&output_root).unwrap();
+    }
+
+    #[test]
+    fn a_panicking_worker_reports_its_files_as_failed() {
+        let input_root: PathBuf = new_sample_tree("panic-input");
+        let output_root: PathBuf = new_temp_folder("panic-output");
+        let relative_paths: Vec<PathBuf> = find_files(&input_root, &[], &[], &output_root).unwrap();
+        let results: Vec<FileResult> = process_files(
+            &input_root,
+            &output_root,
+            &relative_paths,
+            2,
+            &|_| {},
+            |input_path, _| {
+                if input_path.ends_with("c.txt") {
+                    panic!("a cipher bug");
+                }
+                Ok(1)
+            },
+        );
+        assert_eq!(results.len(), relative_paths.len());
+        let failed_count: usize = results
+            .iter()
+            .filter(|result| result.outcome.is_err())
+            .count();
+        assert!(failed_count >= 1);
+        assert!(results[2].outcome.is_err());
+        assert!(format_summary(&results).contains(&format!("{} failed", failed_count)));
+        fs::remove_dir_all(&input_root).unwrap();
+        fs::remove_dir_all(&output_root).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+//! Classical ciphers behind a common `Cipher` trait, selectable by name.
+
+pub mod affine;
+mod alphabet;
+pub mod atbash;
+pub mod byte_shift;
+pub mod caesar;
+pub mod chacha20_poly1305;
+pub mod playfair;
+pub mod rail_fence;
+pub mod rot13;
+pub mod vigenere;
+pub mod xor;
+
+pub use alphabet::{new_alphabet, Alphabet, ALPHABETS};
+
+/// The number of letters of the English alphabet, in every case.
+pub const CASE_LENGTH: u8 = 26;
+/// The code of the first uppercase ASCII letter.
+pub const UPPER_CASE_BASE: u8 = b'A';
+/// The code of the first lowercase ASCII letter.
+pub const LOWER_CASE_BASE: u8 = b'a';
+/// The PBKDF2 iterations used to derive a key from a password, as recommended by OWASP.
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+/// The highest PBKDF2 iterations accepted, so a crafted file header can not hang decryption.
+pub const MAX_KDF_ITERATIONS: u32 = 10 * DEFAULT_KDF_ITERATIONS;
+
+/// An `enum` with the operations a cipher can apply to a text.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Turns a clean text into a ciphered one.
+    Encrypt,
+    /// Turns a ciphered text back into the clean one.
+    Decrypt,
+}
+
+/// A function that encrypts or decrypts a text char by char, keeping the key position.
+pub type CharStream<'a> = Box<dyn FnMut(char) -> char + 'a>;
+
+/// A `trait` for the ciphers that encrypt and decrypt texts.
+///
+/// The key is parsed when the cipher is created, so applying it never fails.
+pub trait Cipher {
+    /// Encrypts a clean text, returning the ciphered text.
+    fn encrypt(&self, clean_text: &str) -> String;
+    /// Decrypts a ciphered text, returning the clean text.
+    fn decrypt(&self, ciphered_text: &str) -> String;
+    /// Encrypts or decrypts a text depending on the mode.
+    fn apply(&self, text: &str, mode: Mode) -> String {
+        match mode {
+            Mode::Encrypt => self.encrypt(text),
+            Mode::Decrypt => self.decrypt(text),
+        }
+    }
+    /// Creates a [`CharStream`] to process a text in chunks, with the same result as [`Cipher::apply`].
+    ///
+    /// Returns None for the ciphers that need the whole text, like the transpositions.
+    fn char_stream(&self, _mode: Mode) -> Option<CharStream<'_>> {
+        None
+    }
+}
+
+/// A `trait` for the ciphers that encrypt and decrypt any bytes, not only texts.
+///
+/// Applying them can fail, like an authenticated cipher that detects a modified file.
+pub trait ByteCipher {
+    /// Encrypts some clean bytes, returning the ciphered bytes.
+    fn encrypt_bytes(&self, clean_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error>;
+    /// Decrypts some ciphered bytes, returning the clean bytes.
+    fn decrypt_bytes(&self, ciphered_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error>;
+    /// Encrypts or decrypts some bytes depending on the mode.
+    fn apply_bytes(&self, bytes: &[u8], mode: Mode) -> Result<Vec<u8>, std::io::Error> {
+        match mode {
+            Mode::Encrypt => self.encrypt_bytes(bytes),
+            Mode::Decrypt => self.decrypt_bytes(bytes),
+        }
+    }
+}
+
+/// A `struct` that describes a cipher available by name.
+///
+/// The binary ciphers work with the bytes of any file and implement [`ByteCipher`],
+/// while the others work with texts and implement [`Cipher`].
+/// The id identifies the cipher in the containers, so it never changes.
+pub struct CipherInfo {
+    /// The number stored in the containers for this cipher.
+    pub id: u8,
+    /// The name used to choose the cipher, like `vigenere`.
+    pub name: &'static str,
+    /// Whether the cipher needs a key.
+    pub needs_key: bool,
+    /// Whether the cipher works with any bytes instead of texts.
+    pub binary: bool,
+    /// A line that explains the cipher, printed in the help.
+    pub description: &'static str,
+}
+
+/// The descriptions of all the ciphers, being the first one the default.
+pub const CIPHERS: [CipherInfo; 10] = [
+    CipherInfo {
+        id: 1,
+        name: "caesar",
+        needs_key: true,
+        binary: false,
+        description: "Shifts each char by the code of the next key char.",
+    },
+    CipherInfo {
+        id: 2,
+        name: "vigenere",
+        needs_key: true,
+        binary: false,
+        description: "Shifts each letter by the position of the next key letter.",
+    },
+    CipherInfo {
+        id: 3,
+        name: "atbash",
+        needs_key: false,
+        binary: false,
+        description: "Reverses the alphabet, so a becomes z.",
+    },
+    CipherInfo {
+        id: 4,
+        name: "affine",
+        needs_key: true,
+        binary: false,
+        description: "Maps each letter x to a*x+b, with a key like 5,8.",
+    },
+    CipherInfo {
+        id: 5,
+        name: "rot13",
+        needs_key: false,
+        binary: false,
+        description: "Shifts each English letter by 13, so it is its own inverse.",
+    },
+    CipherInfo {
+        id: 6,
+        name: "playfair",
+        needs_key: true,
+        binary: false,
+        description: "Encrypts pairs of English letters using a 5x5 key square.",
+    },
+    CipherInfo {
+        id: 7,
+        name: "rail-fence",
+        needs_key: true,
+        binary: false,
+        description: "Writes the text in zigzag over a number of rails.",
+    },
+    CipherInfo {
+        id: 8,
+        name: "byte-shift",
+        needs_key: true,
+        binary: true,
+        description: "Shifts each byte of any file by the next key byte.",
+    },
+    CipherInfo {
+        id: 9,
+        name: "xor",
+        needs_key: true,
+        binary: true,
+        description: "Combines each byte of any file with the next key byte.",
+    },
+    CipherInfo {
+        id: 10,
+        name: "chacha20",
+        needs_key: true,
+        binary: true,
+        description: "Authenticated ChaCha20-Poly1305 with a key derived from the password.",
+    },
+];
+
+/// Gets the description of a cipher by its name.
+/// ### Arguments
+/// * `name` - A string slice with the name of the cipher.
+/// ### Returns
+/// * `Option<&CipherInfo>` - Some description, or None if the cipher is unknown.
+pub fn get_cipher_info(name: &str) -> Option<&'static CipherInfo> {
+    CIPHERS.iter().find(|info| info.name == name)
+}
+
+/// Creates a cipher by its name, parsing the key it needs.
+/// ### Arguments
+/// * `name` - A string slice with the name of the cipher.
+/// * `key_string` - A string slice with the key, ignored by the ciphers without key.
+/// * `alphabet` - The `Alphabet` of the letters to encrypt, ignored by the ciphers that
+///   only know the English one.
+/// ### Returns
+/// * `Result<Box<dyn Cipher>, std::io::Error>` - The cipher, or an error if the name or the key are not valid.
+/// ### Example
+/// ```
+/// use functions::ciphers::{new_alphabet, new_cipher, Mode};
+///
+/// let cipher = new_cipher("caesar", "c", &new_alphabet("spanish").unwrap()).unwrap();
+/// assert_eq!(cipher.apply("niño", Mode::Encrypt), "ezfg");
+/// assert!(new_cipher("affine", "2,3", &new_alphabet("english").unwrap()).is_err());
+/// ```
+pub fn new_cipher(
+    name: &str,
+    key_string: &str,
+    alphabet: &Alphabet,
+) -> Result<Box<dyn Cipher>, std::io::Error> {
+    let cipher: Box<dyn Cipher> = match name {
+        "caesar" => Box::new(caesar::CaesarCipher::new(key_string, alphabet)?),
+        "vigenere" => Box::new(vigenere::VigenereCipher::new(key_string, alphabet)?),
+        "atbash" => Box::new(atbash::AtbashCipher::new(alphabet)),
+        "affine" => Box::new(affine::AffineCipher::new(key_string, alphabet)?),
+        "rot13" => Box::new(rot13::Rot13Cipher::new()),
+        "playfair" => Box::new(playfair::PlayfairCipher::new(key_string)?),
+        "rail-fence" => Box::new(rail_fence::RailFenceCipher::new(key_string)?),
+        _ => {
+            return Err(invalid_key(&format!("⚠️ - Unknown cipher {}.", name)));
+        }
+    };
+    Ok(cipher)
+}
+
+/// Creates a binary cipher by its name, parsing the key it needs.
+/// ### Arguments
+/// * `name` - A string slice with the name of the binary cipher.
+/// * `key_string` - A string slice with the key, whose bytes are used as shift values
+///   or as the password to derive a key.
+/// * `kdf_iterations` - The cost to derive a key from the password, for the ciphers that do it.
+/// ### Returns
+/// * `Result<Box<dyn ByteCipher>, std::io::Error>` - The cipher, or an error if the name or the key are not valid.
+/// ### Example
+/// ```
+/// use functions::ciphers::{new_byte_cipher, Mode};
+///
+/// let cipher = new_byte_cipher("chacha20", "password", 1_000).unwrap();
+/// let encrypted: Vec<u8> = cipher.apply_bytes(&[0, 1, 2, 255], Mode::Encrypt).unwrap();
+/// assert_eq!(cipher.apply_bytes(&encrypted, Mode::Decrypt).unwrap(), [0, 1, 2, 255]);
+/// let wrong_cipher = new_byte_cipher("chacha20", "wrong", 1_000).unwrap();
+/// assert!(wrong_cipher.apply_bytes(&encrypted, Mode::Decrypt).is_err());
+/// ```
+pub fn new_byte_cipher(
+    name: &str,
+    key_string: &str,
+    kdf_iterations: u32,
+) -> Result<Box<dyn ByteCipher>, std::io::Error> {
+    let cipher: Box<dyn ByteCipher> = match name {
+        "byte-shift" => Box::new(byte_shift::ByteShiftCipher::new(key_string)?),
+        "xor" => Box::new(xor::XorCipher::new(key_string)?),
+        "chacha20" => Box::new(chacha20_poly1305::ChaCha20Poly1305Cipher::new(
+            key_string,
+            kdf_iterations,
+        )?),
+        _ => {
+            return Err(invalid_key(&format!(
+                "⚠️ - Unknown binary cipher {}.",
+                name
+            )));
+        }
+    };
+    Ok(cipher)
+}
+
+/// Gets the base code for a character if it is an ASCII alphabetic character.
+/// ### Arguments
+/// * `the_char` - A `char` that holds the character to check.
+/// ### Returns
+/// * `Option<u8>` - Some base code for the character or None
+/// ### Example
+/// ```
+/// use functions::ciphers::get_base_code_option;
+///
+/// assert_eq!(get_base_code_option('q'), Some(b'a'));
+/// assert_eq!(get_base_code_option('Q'), Some(b'A'));
+/// assert_eq!(get_base_code_option('7'), None);
+/// ```
+pub fn get_base_code_option(the_char: char) -> Option<u8> {
+    if !the_char.is_ascii_alphabetic() {
+        return None;
+    }
+    let base_case_code: u8 = if the_char.is_ascii_lowercase() {
+        LOWER_CASE_BASE
+    } else {
+        UPPER_CASE_BASE
+    };
+    Some(base_case_code)
+}
+
+/// Creates an error for a key that a cipher can not use.
+pub fn invalid_key(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
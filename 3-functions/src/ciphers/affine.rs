@@ -0,0 +1,127 @@
+//! The affine cipher, mapping each letter position x to (a * x + b) mod the alphabet length.
+
+use super::{invalid_key, Alphabet, CharStream, Cipher, Mode};
+
+/// A `struct` for the **affine cipher**, holding the multiplier, its inverse, the shift
+/// and the alphabet.
+pub struct AffineCipher {
+    multiplier: usize,
+    inverse: usize,
+    shift: usize,
+    alphabet: Alphabet,
+}
+
+impl AffineCipher {
+    /// Creates an affine cipher from a key like `5,8`.
+    ///
+    /// The multiplier must be coprime with the alphabet length, 26 in English,
+    /// so every letter maps to a different one.
+    pub fn new(key_string: &str, alphabet: &Alphabet) -> Result<AffineCipher, std::io::Error> {
+        let error_message: &str = "⚠️ - The affine key must be two numbers like 5,8.";
+        let (multiplier, shift) = key_string
+            .split_once(',')
+            .ok_or_else(|| invalid_key(error_message))?;
+        let multiplier: usize = multiplier
+            .trim()
+            .parse()
+            .map_err(|_| invalid_key(error_message))?;
+        let shift: usize = shift
+            .trim()
+            .parse()
+            .map_err(|_| invalid_key(error_message))?;
+        let length: usize = alphabet.len();
+        let multiplier: usize = multiplier % length;
+        let inverse: usize = match (1..length)
+            .find(|candidate| (multiplier * candidate) % length == 1)
+        {
+            Some(inverse) => inverse,
+            None => {
+                return Err(invalid_key(&format!(
+                    "⚠️ - The affine multiplier must be coprime with the {} letters of the alphabet.",
+                    length
+                )));
+            }
+        };
+        Ok(AffineCipher {
+            multiplier,
+            inverse,
+            shift: shift % length,
+            alphabet: alphabet.clone(),
+        })
+    }
+
+    /// Encrypts a letter, mapping its position x to a*x+b.
+    fn encrypt_char(&self, clean_char: char) -> char {
+        self.alphabet.map_letter(clean_char, |position| {
+            self.multiplier * position + self.shift
+        })
+    }
+
+    /// Decrypts a letter, mapping its position y to a'*(y-b), where a' is the inverse of a.
+    fn decrypt_char(&self, ciphered_char: char) -> char {
+        self.alphabet.map_letter(ciphered_char, |position| {
+            self.inverse * (position + self.alphabet.len() - self.shift)
+        })
+    }
+}
+
+impl Cipher for AffineCipher {
+    fn encrypt(&self, clean_text: &str) -> String {
+        clean_text
+            .chars()
+            .map(|clean_char| self.encrypt_char(clean_char))
+            .collect()
+    }
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        ciphered_text
+            .chars()
+            .map(|ciphered_char| self.decrypt_char(ciphered_char))
+            .collect()
+    }
+    fn char_stream(&self, mode: Mode) -> Option<CharStream<'_>> {
+        match mode {
+            Mode::Encrypt => Some(Box::new(|the_char| self.encrypt_char(the_char))),
+            Mode::Decrypt => Some(Box::new(|the_char| self.decrypt_char(the_char))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::new_alphabet;
+
+    #[test]
+    fn decrypt_inverts_encrypt_for_every_valid_key() {
+        let clean_text: &str = "Affine cipher, niño 🦀 ÑANDÚ!";
+        for alphabet in [Alphabet::english(), new_alphabet("spanish").unwrap()] {
+            for key_string in ["1,0", "5,8", "25,3", "27,100"] {
+                let cipher: AffineCipher = match AffineCipher::new(key_string, &alphabet) {
+                    Ok(cipher) => cipher,
+                    Err(_) => continue,
+                };
+                let ciphered_text: String = cipher.encrypt(clean_text);
+                assert_eq!(cipher.decrypt(&ciphered_text), clean_text, "{}", key_string);
+            }
+        }
+        let identity: AffineCipher = AffineCipher::new("1,0", &Alphabet::english()).unwrap();
+        assert_eq!(identity.encrypt(clean_text), clean_text);
+    }
+
+    #[test]
+    fn known_answer_and_invalid_keys() {
+        let cipher: AffineCipher = AffineCipher::new("5,8", &Alphabet::english()).unwrap();
+        assert_eq!(cipher.encrypt("affine cipher"), "ihhwvc swfrcp");
+        for key_string in ["2,3", "13,1", "0,5", "5", "a,b"] {
+            let error: std::io::Error = AffineCipher::new(key_string, &Alphabet::english())
+                .err()
+                .unwrap();
+            assert_eq!(
+                error.kind(),
+                std::io::ErrorKind::InvalidInput,
+                "{}",
+                key_string
+            );
+        }
+    }
+}
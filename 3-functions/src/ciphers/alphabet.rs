@@ -0,0 +1,166 @@
+//! The alphabets used by the ciphers, so letters like ñ can be encrypted too.
+
+use super::invalid_key;
+
+/// A `struct` that describes an alphabet available by name.
+pub struct AlphabetInfo {
+    /// The name used to choose the alphabet, like `spanish`.
+    pub name: &'static str,
+    /// The lowercase letters in order.
+    pub letters: &'static str,
+}
+
+/// The named alphabets, being the first one the default.
+pub const ALPHABETS: [AlphabetInfo; 2] = [
+    AlphabetInfo {
+        name: "english",
+        letters: "abcdefghijklmnopqrstuvwxyz",
+    },
+    AlphabetInfo {
+        name: "spanish",
+        letters: "abcdefghijklmnñopqrstuvwxyz",
+    },
+];
+
+/// A `struct` for an **alphabet**, holding the lowercase and uppercase form of every letter.
+///
+/// Letters without case, like digits, have the same char in both forms.
+/// Any char that is not in the alphabet is left unchanged by the ciphers.
+#[derive(Clone)]
+pub struct Alphabet {
+    lower_letters: Vec<char>,
+    upper_letters: Vec<char>,
+}
+
+impl Alphabet {
+    /// Creates an alphabet from a string with its letters in order, in any case.
+    /// ### Arguments
+    /// * `letters` - A string slice with the letters, like `abcdefghijklmnñopqrstuvwxyz`.
+    /// ### Returns
+    /// * `Result<Alphabet, std::io::Error>` - The alphabet, or an error if it has less than
+    ///   two letters or a repeated letter.
+    pub fn new(letters: &str) -> Result<Alphabet, std::io::Error> {
+        let lower_letters: Vec<char> = letters.chars().map(to_lower_char).collect();
+        let upper_letters: Vec<char> = letters.chars().map(to_upper_char).collect();
+        if lower_letters.len() < 2 {
+            return Err(invalid_key(
+                "⚠️ - The alphabet must contain at least two letters.",
+            ));
+        }
+        for (position, letter) in lower_letters.iter().enumerate() {
+            let repeated: bool = lower_letters[position + 1..].contains(letter)
+                || upper_letters[position + 1..].contains(&upper_letters[position]);
+            if repeated {
+                return Err(invalid_key(&format!(
+                    "⚠️ - The letter {} is repeated in the alphabet.",
+                    letter
+                )));
+            }
+        }
+        Ok(Alphabet {
+            lower_letters,
+            upper_letters,
+        })
+    }
+
+    /// Creates the English alphabet, with the 26 ASCII letters.
+    pub fn english() -> Alphabet {
+        Alphabet {
+            lower_letters: ('a'..='z').collect(),
+            upper_letters: ('A'..='Z').collect(),
+        }
+    }
+
+    /// Gets the letters of the alphabet in order and in lowercase, to create it again.
+    pub fn letters(&self) -> String {
+        self.lower_letters.iter().collect()
+    }
+
+    /// Gets the number of letters of the alphabet.
+    pub fn len(&self) -> usize {
+        self.lower_letters.len()
+    }
+
+    /// Checks if the alphabet has no letters, which never happens once created.
+    pub fn is_empty(&self) -> bool {
+        self.lower_letters.is_empty()
+    }
+
+    /// Gets the position of a char in the alphabet and whether it is uppercase.
+    /// ### Arguments
+    /// * `the_char` - A `char` to look for.
+    /// ### Returns
+    /// * `Option<(usize, bool)>` - Some position and uppercase flag, or None if it is not a letter.
+    pub fn get_position(&self, the_char: char) -> Option<(usize, bool)> {
+        if let Some(position) = self
+            .lower_letters
+            .iter()
+            .position(|letter| *letter == the_char)
+        {
+            return Some((position, false));
+        }
+        self.upper_letters
+            .iter()
+            .position(|letter| *letter == the_char)
+            .map(|position| (position, true))
+    }
+
+    /// Maps the position of a letter in the alphabet, keeping its case.
+    /// If the character is not a letter of the alphabet, then it is left unchanged.
+    /// ### Arguments
+    /// * `the_char` - A `char` with the character to map.
+    /// * `map_position` - A function from the position of the letter to the new position,
+    ///   that is wrapped around the length of the alphabet.
+    /// ### Returns
+    /// * `char` - The mapped character.
+    pub fn map_letter(&self, the_char: char, map_position: impl Fn(usize) -> usize) -> char {
+        let (position, is_upper) = match self.get_position(the_char) {
+            None => return the_char,
+            Some(found) => found,
+        };
+        let new_position: usize = map_position(position) % self.len();
+        if is_upper {
+            self.upper_letters[new_position]
+        } else {
+            self.lower_letters[new_position]
+        }
+    }
+}
+
+/// Gets an alphabet by its name, or creates it from its letters if no name matches.
+/// ### Arguments
+/// * `name_or_letters` - A string slice with the name of the alphabet or its letters.
+/// ### Returns
+/// * `Result<Alphabet, std::io::Error>` - The alphabet, or an error if the letters are not valid.
+/// ### Example
+/// ```
+/// use functions::ciphers::new_alphabet;
+///
+/// assert_eq!(new_alphabet("spanish").unwrap().len(), 27);
+/// assert_eq!(new_alphabet("ΑΒΓΔ").unwrap().get_position('γ'), Some((2, false)));
+/// assert!(new_alphabet("abca").is_err());
+/// ```
+pub fn new_alphabet(name_or_letters: &str) -> Result<Alphabet, std::io::Error> {
+    match ALPHABETS.iter().find(|info| info.name == name_or_letters) {
+        Some(info) => Alphabet::new(info.letters),
+        None => Alphabet::new(name_or_letters),
+    }
+}
+
+/// Gets the lowercase form of a char, or the same char if it has no single-char lowercase.
+fn to_lower_char(the_char: char) -> char {
+    let mut lower_chars = the_char.to_lowercase();
+    match (lower_chars.next(), lower_chars.next()) {
+        (Some(lower_char), None) => lower_char,
+        _ => the_char,
+    }
+}
+
+/// Gets the uppercase form of a char, or the same char if it has no single-char uppercase.
+fn to_upper_char(the_char: char) -> char {
+    let mut upper_chars = the_char.to_uppercase();
+    match (upper_chars.next(), upper_chars.next()) {
+        (Some(upper_char), None) => upper_char,
+        _ => the_char,
+    }
+}
@@ -0,0 +1,64 @@
+//! The Atbash cipher, reversing the alphabet.
+
+use super::{Alphabet, CharStream, Cipher, Mode};
+
+/// A `struct` for the **Atbash cipher**, that needs no key, holding the alphabet to reverse.
+pub struct AtbashCipher {
+    alphabet: Alphabet,
+}
+
+impl AtbashCipher {
+    /// Creates an Atbash cipher that reverses the letters of an alphabet.
+    pub fn new(alphabet: &Alphabet) -> AtbashCipher {
+        AtbashCipher {
+            alphabet: alphabet.clone(),
+        }
+    }
+}
+
+impl Cipher for AtbashCipher {
+    fn encrypt(&self, clean_text: &str) -> String {
+        clean_text
+            .chars()
+            .map(|clean_char| atbash_char(clean_char, &self.alphabet))
+            .collect()
+    }
+    /// The Atbash cipher is its own inverse, so decrypting is encrypting again.
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        self.encrypt(ciphered_text)
+    }
+    fn char_stream(&self, _mode: Mode) -> Option<CharStream<'_>> {
+        Some(Box::new(|the_char| atbash_char(the_char, &self.alphabet)))
+    }
+}
+
+/// Reverses a letter in the alphabet, keeping its case.
+/// If the character is not a letter of the alphabet, then it is left unchanged.
+/// ### Arguments
+/// * `the_char` - A `char` with the character to reverse.
+/// * `alphabet` - The `Alphabet` to reverse.
+/// ### Returns
+/// * `char` - The reversed character.
+fn atbash_char(the_char: char, alphabet: &Alphabet) -> char {
+    alphabet.map_letter(the_char, |position| alphabet.len() - 1 - position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::new_alphabet;
+
+    #[test]
+    fn decrypt_inverts_encrypt_in_every_alphabet() {
+        let clean_text: &str = "Atbash, niño 🦀 ÑANDÚ!";
+        for alphabet in [Alphabet::english(), new_alphabet("spanish").unwrap()] {
+            let cipher: AtbashCipher = AtbashCipher::new(&alphabet);
+            assert_eq!(cipher.decrypt(&cipher.encrypt(clean_text)), clean_text);
+            assert_eq!(cipher.encrypt(""), "");
+        }
+        assert_eq!(
+            AtbashCipher::new(&Alphabet::english()).encrypt("Abz"),
+            "Zya"
+        );
+    }
+}
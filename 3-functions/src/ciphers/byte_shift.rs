@@ -0,0 +1,68 @@
+//! The byte shift cipher, a Caesar cipher with a rotating key over the 256 byte values.
+
+use super::{invalid_key, ByteCipher};
+
+/// A `struct` for the **byte shift cipher**, holding the bytes of the key.
+///
+/// Every byte of the input is shifted, so it works with any file, not only texts.
+pub struct ByteShiftCipher {
+    key_bytes: Vec<u8>,
+}
+
+impl ByteShiftCipher {
+    /// Creates a byte shift cipher, returning an error if the key is empty.
+    pub fn new(key_string: &str) -> Result<ByteShiftCipher, std::io::Error> {
+        if key_string.is_empty() {
+            return Err(invalid_key("⚠️ - The byte shift key can not be empty."));
+        }
+        Ok(ByteShiftCipher {
+            key_bytes: key_string.as_bytes().to_vec(),
+        })
+    }
+}
+
+impl ByteCipher for ByteShiftCipher {
+    /// Adds the next key byte to every byte, wrapping around 256.
+    fn encrypt_bytes(&self, clean_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        let ciphered_bytes: Vec<u8> = clean_bytes
+            .iter()
+            .zip(self.key_bytes.iter().cycle())
+            .map(|(clean_byte, key_byte)| clean_byte.wrapping_add(*key_byte))
+            .collect();
+        Ok(ciphered_bytes)
+    }
+    /// Subtracts the next key byte from every byte, wrapping around 0.
+    fn decrypt_bytes(&self, ciphered_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        let clean_bytes: Vec<u8> = ciphered_bytes
+            .iter()
+            .zip(self.key_bytes.iter().cycle())
+            .map(|(ciphered_byte, key_byte)| ciphered_byte.wrapping_sub(*key_byte))
+            .collect();
+        Ok(clean_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_inverts_encrypt_for_every_byte_value() {
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        for key_string in ["k", "clave", "ñ🦀\u{0}"] {
+            let cipher: ByteShiftCipher = ByteShiftCipher::new(key_string).unwrap();
+            let ciphered_bytes: Vec<u8> = cipher.encrypt_bytes(&all_bytes).unwrap();
+            assert_ne!(ciphered_bytes, all_bytes);
+            assert_eq!(cipher.decrypt_bytes(&ciphered_bytes).unwrap(), all_bytes);
+            assert!(cipher.encrypt_bytes(&[]).unwrap().is_empty());
+        }
+        let cipher: ByteShiftCipher = ByteShiftCipher::new("\u{1}").unwrap();
+        assert_eq!(cipher.encrypt_bytes(&[0, 254, 255]).unwrap(), [1, 255, 0]);
+    }
+
+    #[test]
+    fn an_empty_key_is_invalid_input() {
+        let error: std::io::Error = ByteShiftCipher::new("").err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
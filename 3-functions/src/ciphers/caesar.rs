@@ -0,0 +1,247 @@
+//! The Caesar cipher, rotating the shift values taken from the codes of the key chars.
+
+use super::{invalid_key, Alphabet, CharStream, Cipher, Mode};
+
+/// A `struct` for the **Caesar cipher** with a rotating key, over an alphabet.
+pub struct CaesarCipher {
+    key_string: String,
+    alphabet: Alphabet,
+}
+
+impl CaesarCipher {
+    /// Creates a Caesar cipher, returning an error if the key is empty.
+    pub fn new(key_string: &str, alphabet: &Alphabet) -> Result<CaesarCipher, std::io::Error> {
+        if key_string.is_empty() {
+            return Err(invalid_key("⚠️ - The Caesar key can not be empty."));
+        }
+        Ok(CaesarCipher {
+            key_string: key_string.to_string(),
+            alphabet: alphabet.clone(),
+        })
+    }
+}
+
+impl Cipher for CaesarCipher {
+    fn encrypt(&self, clean_text: &str) -> String {
+        caesar_cipher_text(clean_text, &self.key_string, &self.alphabet)
+    }
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        caesar_decipher_text(ciphered_text, &self.key_string, &self.alphabet)
+    }
+    /// Rotates the key over every char, like [`caesar_cipher_text`] does.
+    fn char_stream(&self, mode: Mode) -> Option<CharStream<'_>> {
+        let key_chars: Vec<char> = self.key_string.chars().collect();
+        let mut key_index: usize = 0;
+        Some(Box::new(move |the_char| {
+            let shift: usize = key_chars[key_index] as usize;
+            key_index = (key_index + 1) % key_chars.len();
+            match mode {
+                Mode::Encrypt => caesar_cipher_char(the_char, shift, &self.alphabet),
+                Mode::Decrypt => caesar_decipher_char(the_char, shift, &self.alphabet),
+            }
+        }))
+    }
+}
+
+/// Encrypts a string using the **Caesar cipher**.
+///
+/// This function takes a clean string and a key password as input.
+/// It applies the Caesar cipher to each character in the string,
+/// using the key password as a source of shift values.
+/// The shift values are rotated for each character.
+/// > Note: This function is implemented using loops instead of iterators.
+/// ### Arguments
+/// * `input` - A string slice that holds the clean text to be encrypted.
+/// - `key` - A string slice that holds the key password.
+/// - `alphabet` - The `Alphabet` with the letters to shift.
+/// ### Returns
+/// - `String` - The encrypted text.
+/// ### Example
+/// ```
+/// use functions::ciphers::caesar::caesar_cipher_text;
+/// use functions::ciphers::Alphabet;
+///
+/// let encrypted: String = caesar_cipher_text("hello", "key", &Alphabet::english());
+/// assert_eq!(encrypted, "kbcol");
+/// ```
+pub fn caesar_cipher_text(clean_string: &str, key_string: &str, alphabet: &Alphabet) -> String {
+    let mut key_index: usize = 0;
+    let mut ciphered_string: String = String::new();
+    for clean_char in clean_string.chars() {
+        let key_char: char = key_string.chars().nth(key_index).unwrap_or_default();
+        let shift: usize = key_char as usize;
+        let ciphered_char: char = caesar_cipher_char(clean_char, shift, alphabet);
+        ciphered_string.push(ciphered_char);
+        key_index = get_next_key_index(key_index, key_string);
+    }
+    ciphered_string
+}
+
+/// Decrypts a string encrypted with [`caesar_cipher_text`].
+///
+/// This function takes a ciphered string and the key password used to encrypt it.
+/// It rotates the key shift values in the same way as the encryption,
+/// but subtracts them from each character instead of adding them.
+/// > Note: This function is implemented using loops instead of iterators.
+/// ### Arguments
+/// * `ciphered_string` - A string slice that holds the encrypted text.
+/// - `key_string` - A string slice that holds the key password.
+/// - `alphabet` - The `Alphabet` used to encrypt the text.
+/// ### Returns
+/// - `String` - The decrypted text.
+/// ### Example
+/// ```
+/// use functions::ciphers::caesar::caesar_decipher_text;
+/// use functions::ciphers::Alphabet;
+///
+/// let decrypted: String = caesar_decipher_text("kbcol", "key", &Alphabet::english());
+/// assert_eq!(decrypted, "hello");
+/// ```
+pub fn caesar_decipher_text(
+    ciphered_string: &str,
+    key_string: &str,
+    alphabet: &Alphabet,
+) -> String {
+    let mut key_index: usize = 0;
+    let mut clean_string: String = String::new();
+    for ciphered_char in ciphered_string.chars() {
+        let key_char: char = key_string.chars().nth(key_index).unwrap_or_default();
+        let shift: usize = key_char as usize;
+        let clean_char: char = caesar_decipher_char(ciphered_char, shift, alphabet);
+        clean_string.push(clean_char);
+        key_index = get_next_key_index(key_index, key_string);
+    }
+    clean_string
+}
+
+/// Gets the next rotating index from a key string.
+///
+/// This function is used to rotate the key string for each character.
+/// If the current index is at the end of the key string, then it wraps around to the start.
+/// Otherwise, it increments the current index by 1.
+/// The length of the key is counted in characters, so multi-byte keys rotate correctly,
+/// and an empty key always returns the index 0.
+/// ### Arguments
+/// * `current_index` - A usize that holds the current index.
+/// * `key_string` - A string slice that holds the key password.
+/// ### Returns
+/// * `usize` - The next rotating index.
+/// ### Example
+/// ```
+/// use functions::ciphers::caesar::get_next_key_index;
+///
+/// assert_eq!(get_next_key_index(1, "key"), 2);
+/// assert_eq!(get_next_key_index(2, "key"), 0);
+/// ```
+pub fn get_next_key_index(current_index: usize, key_string: &str) -> usize {
+    let key_length: usize = key_string.chars().count();
+    if key_length == 0 {
+        return 0;
+    }
+    let next_index: usize = current_index + 1;
+    let rotated_index: usize = next_index % key_length;
+    rotated_index
+}
+
+/// Encrypts a character using the **Caesar cipher**.
+///
+/// This function takes a clean character, a shift value and an alphabet as input.
+/// It applies the Caesar cipher to the character using the shift value,
+/// wrapping around the end of the alphabet and keeping the case.
+/// If the character is not a letter of the alphabet, then it is left unchanged.
+/// ### Arguments
+/// * `clean_char` - A character that holds the clean text to be encrypted.
+/// * `shift` - A usize that holds the shift value.
+/// * `alphabet` - The `Alphabet` with the letters to shift.
+/// ### Returns
+/// * `char` - The encrypted character.
+/// ### Example
+/// ```
+/// use functions::ciphers::caesar::caesar_cipher_char;
+/// use functions::ciphers::Alphabet;
+///
+/// assert_eq!(caesar_cipher_char('a', 3, &Alphabet::english()), 'd');
+/// assert_eq!(caesar_cipher_char('Z', 3, &Alphabet::english()), 'C');
+/// ```
+pub fn caesar_cipher_char(clean_char: char, shift: usize, alphabet: &Alphabet) -> char {
+    let shift: usize = shift % alphabet.len();
+    let ciphered_char: char = alphabet.map_letter(clean_char, |position| position + shift);
+    ciphered_char
+}
+
+/// Decrypts a character encrypted with [`caesar_cipher_char`].
+///
+/// This function takes a ciphered character, the shift value and the alphabet used to encrypt it.
+/// It shifts the character backwards, wrapping around the start of the alphabet.
+/// If the character is not a letter of the alphabet, then it is left unchanged.
+/// ### Arguments
+/// * `ciphered_char` - A character that holds the encrypted text.
+/// * `shift` - A usize that holds the shift value.
+/// * `alphabet` - The `Alphabet` used to encrypt the character.
+/// ### Returns
+/// * `char` - The decrypted character.
+/// ### Example
+/// ```
+/// use functions::ciphers::caesar::caesar_decipher_char;
+/// use functions::ciphers::Alphabet;
+///
+/// assert_eq!(caesar_decipher_char('d', 3, &Alphabet::english()), 'a');
+/// ```
+pub fn caesar_decipher_char(ciphered_char: char, shift: usize, alphabet: &Alphabet) -> char {
+    let shift: usize = shift % alphabet.len();
+    let clean_char: char =
+        alphabet.map_letter(ciphered_char, |position| position + alphabet.len() - shift);
+    clean_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::new_alphabet;
+
+    /// The chars the texts and keys are generated from, with multi-byte and non-letter ones.
+    const SAMPLE_CHARS: [char; 16] = [
+        'a', 'z', 'M', 'ñ', 'Ñ', 'é', 'ü', ' ', '.', '7', '\n', 'ß', 'Ω', '語', '🦀', 'q',
+    ];
+
+    /// A xorshift generator, so the generated cases are the same in every run.
+    fn next_random(state: &mut u64) -> usize {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state as usize
+    }
+
+    /// Generates a string of `min_length` to `max_length` chars taken from the sample.
+    fn random_string(state: &mut u64, min_length: usize, max_length: usize) -> String {
+        let length: usize = min_length + next_random(state) % (max_length - min_length + 1);
+        (0..length)
+            .map(|_| SAMPLE_CHARS[next_random(state) % SAMPLE_CHARS.len()])
+            .collect()
+    }
+
+    #[test]
+    fn decrypt_inverts_encrypt_for_generated_texts_and_keys() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for alphabet in [Alphabet::english(), new_alphabet("spanish").unwrap()] {
+            for _ in 0..500 {
+                let clean_text: String = random_string(&mut state, 0, 64);
+                let key_string: String = random_string(&mut state, 1, 8);
+                let ciphered_text: String = caesar_cipher_text(&clean_text, &key_string, &alphabet);
+                assert_eq!(
+                    caesar_decipher_text(&ciphered_text, &key_string, &alphabet),
+                    clean_text,
+                    "key {:?}",
+                    key_string
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn multi_char_keys_rotate_their_shifts() {
+        let alphabet: Alphabet = Alphabet::english();
+        assert_eq!(caesar_cipher_text("aaaa", "bc", &alphabet), "uvuv");
+        assert_eq!(caesar_decipher_text("uvuv", "bc", &alphabet), "aaaa");
+    }
+}
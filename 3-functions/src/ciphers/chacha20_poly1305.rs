@@ -0,0 +1,143 @@
+//! Authenticated encryption with ChaCha20-Poly1305 and a key derived from a password.
+
+use super::{invalid_key, ByteCipher, MAX_KDF_ITERATIONS};
+use crate::crypto::chacha20::{KEY_SIZE, NONCE_SIZE};
+use crate::crypto::secret::{zeroize, SecretString};
+use crate::crypto::sha256::pbkdf2_sha256;
+use crate::crypto::{fill_random, open, seal};
+
+/// The bytes that start every file encrypted by this cipher.
+const MAGIC: &[u8; 4] = b"RSAE";
+/// The version of the file header, increased when its layout changes.
+const VERSION: u8 = 1;
+/// The size in bytes of the random salt of the key derivation.
+const SALT_SIZE: usize = 16;
+/// The size in bytes of the header: magic, version, iterations, salt and nonce.
+const HEADER_SIZE: usize = MAGIC.len() + 1 + 4 + SALT_SIZE + NONCE_SIZE;
+
+/// A `struct` for the **ChaCha20-Poly1305** authenticated encryption, holding the password
+/// and the PBKDF2 iterations used to encrypt.
+///
+/// Every encryption derives a new key from the password and a random salt,
+/// and uses a random nonce; they are stored in the header of the file with the iterations,
+/// so decryption always uses the cost the file was encrypted with,
+/// as long as it is not above [`MAX_KDF_ITERATIONS`].
+/// The header is authenticated too, so any change to the file makes decryption fail.
+/// The password and the derived keys are overwritten with zeros when no longer needed.
+pub struct ChaCha20Poly1305Cipher {
+    password: SecretString,
+    iterations: u32,
+}
+
+impl ChaCha20Poly1305Cipher {
+    /// Creates a ChaCha20-Poly1305 cipher, returning an error if the password is empty
+    /// or the iterations are not between 1 and [`MAX_KDF_ITERATIONS`].
+    pub fn new(
+        key_string: &str,
+        iterations: u32,
+    ) -> Result<ChaCha20Poly1305Cipher, std::io::Error> {
+        if key_string.is_empty() {
+            return Err(invalid_key("⚠️ - The chacha20 password can not be empty."));
+        }
+        if !(1..=MAX_KDF_ITERATIONS).contains(&iterations) {
+            return Err(invalid_key(&format!(
+                "⚠️ - The KDF iterations must be between 1 and {}.",
+                MAX_KDF_ITERATIONS
+            )));
+        }
+        Ok(ChaCha20Poly1305Cipher {
+            password: SecretString::new(key_string.to_string()),
+            iterations,
+        })
+    }
+}
+
+impl ByteCipher for ChaCha20Poly1305Cipher {
+    /// Writes the header followed by the sealed bytes and the tag.
+    fn encrypt_bytes(&self, clean_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        let mut salt: [u8; SALT_SIZE] = [0; SALT_SIZE];
+        fill_random(&mut salt)?;
+        let mut nonce: [u8; NONCE_SIZE] = [0; NONCE_SIZE];
+        fill_random(&mut nonce)?;
+        let mut header: Vec<u8> = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&self.iterations.to_be_bytes());
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&nonce);
+        let mut key: [u8; KEY_SIZE] =
+            pbkdf2_sha256(self.password.expose().as_bytes(), &salt, self.iterations);
+        let sealed_bytes: Result<Vec<u8>, std::io::Error> =
+            seal(&key, &nonce, &header, clean_bytes);
+        zeroize(&mut key);
+        header.extend_from_slice(&sealed_bytes?);
+        Ok(header)
+    }
+    /// Reads the header and refuses to return anything if the tag does not match.
+    fn decrypt_bytes(&self, ciphered_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        if ciphered_bytes.len() < HEADER_SIZE || &ciphered_bytes[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data(
+                "⚠️ - The input is not a file encrypted with chacha20.",
+            ));
+        }
+        let (header, sealed_bytes) = ciphered_bytes.split_at(HEADER_SIZE);
+        let version: u8 = header[MAGIC.len()];
+        if version != VERSION {
+            return Err(invalid_data(&format!(
+                "⚠️ - The chacha20 file version {} is not supported.",
+                version
+            )));
+        }
+        let mut position: usize = MAGIC.len() + 1;
+        let iterations: u32 = u32::from_be_bytes([
+            header[position],
+            header[position + 1],
+            header[position + 2],
+            header[position + 3],
+        ]);
+        if !(1..=MAX_KDF_ITERATIONS).contains(&iterations) {
+            return Err(invalid_data(&format!(
+                "⚠️ - The chacha20 file asks for {} KDF iterations, out of 1 to {}.",
+                iterations, MAX_KDF_ITERATIONS
+            )));
+        }
+        position += 4;
+        let salt: &[u8] = &header[position..position + SALT_SIZE];
+        position += SALT_SIZE;
+        let mut nonce: [u8; NONCE_SIZE] = [0; NONCE_SIZE];
+        nonce.copy_from_slice(&header[position..position + NONCE_SIZE]);
+        let mut key: [u8; KEY_SIZE] =
+            pbkdf2_sha256(self.password.expose().as_bytes(), salt, iterations);
+        let clean_bytes: Option<Vec<u8>> = open(&key, &nonce, header, sealed_bytes);
+        zeroize(&mut key);
+        clean_bytes.ok_or_else(|| {
+            invalid_data("⚠️ - The authentication tag does not match: wrong key or modified file.")
+        })
+    }
+}
+
+/// Creates an error for an input that can not be decrypted.
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterations_above_the_ceiling_are_rejected_before_deriving_the_key() {
+        let cipher: ChaCha20Poly1305Cipher =
+            ChaCha20Poly1305Cipher::new("password", 1_000).unwrap();
+        let mut ciphered_bytes: Vec<u8> = cipher.encrypt_bytes(b"secret").unwrap();
+        assert_eq!(cipher.decrypt_bytes(&ciphered_bytes).unwrap(), b"secret");
+        let iterations_position: usize = MAGIC.len() + 1;
+        ciphered_bytes[iterations_position..iterations_position + 4]
+            .copy_from_slice(&u32::MAX.to_be_bytes());
+        let error: std::io::Error = cipher.decrypt_bytes(&ciphered_bytes).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("KDF iterations"));
+        assert!(ChaCha20Poly1305Cipher::new("password", MAX_KDF_ITERATIONS + 1).is_err());
+        assert!(ChaCha20Poly1305Cipher::new("password", 0).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! The Playfair cipher, encrypting pairs of letters with a 5x5 key square.
+
+use super::{invalid_key, Cipher};
+
+const SQUARE_SIZE: usize = 5;
+const FILLER_LETTER: char = 'X';
+const ALTERNATIVE_FILLER_LETTER: char = 'Q';
+
+/// A `struct` for the **Playfair cipher**, holding the 25 letters of the key square.
+///
+/// The letter J is merged with I, and the output has only uppercase letters,
+/// so spaces, punctuation and case are lost when encrypting.
+pub struct PlayfairCipher {
+    square: Vec<char>,
+}
+
+impl PlayfairCipher {
+    /// Creates a Playfair cipher, returning an error if the key has no letters.
+    pub fn new(key_string: &str) -> Result<PlayfairCipher, std::io::Error> {
+        let key_letters: Vec<char> = get_playfair_letters(key_string);
+        if key_letters.is_empty() {
+            return Err(invalid_key(
+                "⚠️ - The Playfair key must contain at least one ASCII letter.",
+            ));
+        }
+        let mut square: Vec<char> = Vec::with_capacity(SQUARE_SIZE * SQUARE_SIZE);
+        for letter in key_letters.into_iter().chain('A'..='Z') {
+            if letter != 'J' && !square.contains(&letter) {
+                square.push(letter);
+            }
+        }
+        Ok(PlayfairCipher { square })
+    }
+
+    /// Gets the row and column of a letter in the key square.
+    fn get_position(&self, letter: char) -> (usize, usize) {
+        let index: usize = self
+            .square
+            .iter()
+            .position(|square_letter| *square_letter == letter)
+            .unwrap_or_default();
+        (index / SQUARE_SIZE, index % SQUARE_SIZE)
+    }
+
+    /// Gets the letter at a row and column of the key square.
+    fn get_letter(&self, row: usize, column: usize) -> char {
+        self.square[(row % SQUARE_SIZE) * SQUARE_SIZE + column % SQUARE_SIZE]
+    }
+
+    /// Encrypts or decrypts all the pairs of letters of a text.
+    /// - Letters in the same row move right, or left to decrypt.
+    /// - Letters in the same column move down, or up to decrypt.
+    /// - Otherwise, each letter takes the column of the other one.
+    fn process_pairs(&self, text: &str, step: usize) -> String {
+        let mut processed_string: String = String::new();
+        for (first, second) in get_pairs(&get_playfair_letters(text)) {
+            let (first_row, first_column) = self.get_position(first);
+            let (second_row, second_column) = self.get_position(second);
+            if first_row == second_row {
+                processed_string.push(self.get_letter(first_row, first_column + step));
+                processed_string.push(self.get_letter(second_row, second_column + step));
+            } else if first_column == second_column {
+                processed_string.push(self.get_letter(first_row + step, first_column));
+                processed_string.push(self.get_letter(second_row + step, second_column));
+            } else {
+                processed_string.push(self.get_letter(first_row, second_column));
+                processed_string.push(self.get_letter(second_row, first_column));
+            }
+        }
+        processed_string
+    }
+}
+
+impl Cipher for PlayfairCipher {
+    fn encrypt(&self, clean_text: &str) -> String {
+        self.process_pairs(clean_text, 1)
+    }
+    /// Moving 4 positions in a row or column of 5 is the same as moving 1 back.
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        self.process_pairs(ciphered_text, SQUARE_SIZE - 1)
+    }
+}
+
+/// Gets the uppercase ASCII letters of a text, replacing J with I.
+fn get_playfair_letters(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|the_char| the_char.is_ascii_alphabetic())
+        .map(|the_char| match the_char.to_ascii_uppercase() {
+            'J' => 'I',
+            letter => letter,
+        })
+        .collect()
+}
+
+/// Splits letters in pairs, adding a filler letter between repeated letters and at the end.
+fn get_pairs(letters: &[char]) -> Vec<(char, char)> {
+    let mut pairs: Vec<(char, char)> = vec![];
+    let mut index: usize = 0;
+    while index < letters.len() {
+        let first: char = letters[index];
+        let filler: char = if first == FILLER_LETTER {
+            ALTERNATIVE_FILLER_LETTER
+        } else {
+            FILLER_LETTER
+        };
+        match letters.get(index + 1) {
+            Some(second) if *second != first => {
+                pairs.push((first, *second));
+                index += 2;
+            }
+            _ => {
+                pairs.push((first, filler));
+                index += 1;
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_inverts_encrypt_up_to_the_filler_letters() {
+        let cipher: PlayfairCipher = PlayfairCipher::new("playfair example").unwrap();
+        let ciphered_text: String = cipher.encrypt("Hide the gold in the tree stump");
+        assert_eq!(ciphered_text, "BMODZBXDNABEKUDMUIXMMOUVIF");
+        assert_eq!(cipher.decrypt(&ciphered_text), "HIDETHEGOLDINTHETREXESTUMP");
+    }
+
+    #[test]
+    fn a_one_letter_key_and_odd_texts_round_trip() {
+        let cipher: PlayfairCipher = PlayfairCipher::new("x").unwrap();
+        for clean_text in ["ABCDEFGHIKLMNOPQRSTUVWXYZ", "XX", "A", ""] {
+            let ciphered_text: String = cipher.encrypt(clean_text);
+            let letters: String = get_pairs(&get_playfair_letters(clean_text))
+                .into_iter()
+                .flat_map(|(first, second)| [first, second])
+                .collect();
+            assert_eq!(cipher.decrypt(&ciphered_text), letters, "{}", clean_text);
+        }
+    }
+
+    #[test]
+    fn keys_without_letters_are_invalid_input() {
+        let error: std::io::Error = PlayfairCipher::new("123 !").err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
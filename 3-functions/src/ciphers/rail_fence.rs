@@ -0,0 +1,132 @@
+//! The rail fence cipher, a transposition writing the text in zigzag over some rails.
+
+use super::{invalid_key, Cipher};
+
+/// The most rails accepted, far more than any text needs to be scrambled.
+pub const MAX_RAILS: usize = 1_000;
+
+/// A `struct` for the **rail fence cipher**, holding the number of rails.
+pub struct RailFenceCipher {
+    rails: usize,
+}
+
+impl RailFenceCipher {
+    /// Creates a rail fence cipher, returning an error if the key is not a number of rails
+    /// from 1 to [`MAX_RAILS`].
+    ///
+    /// A single rail leaves the text as it is.
+    pub fn new(key_string: &str) -> Result<RailFenceCipher, std::io::Error> {
+        match key_string.trim().parse::<usize>() {
+            Ok(rails) if (1..=MAX_RAILS).contains(&rails) => Ok(RailFenceCipher { rails }),
+            _ => Err(invalid_key(&format!(
+                "⚠️ - The rail fence key must be a number of rails from 1 to {}.",
+                MAX_RAILS
+            ))),
+        }
+    }
+
+    /// Gets the rails used for a text of a given length.
+    ///
+    /// The zigzag never goes deeper than the text is long, so the extra rails would be empty.
+    fn get_rail_count(&self, length: usize) -> usize {
+        self.rails.min(length).max(1)
+    }
+
+    /// Gets the rail of every position of a text of a given length, going down and up in zigzag.
+    fn get_rails(&self, length: usize) -> Vec<usize> {
+        let rail_count: usize = self.get_rail_count(length);
+        let cycle: usize = (2 * (rail_count - 1)).max(1);
+        (0..length)
+            .map(|position| {
+                let step: usize = position % cycle;
+                if step < rail_count {
+                    step
+                } else {
+                    cycle - step
+                }
+            })
+            .collect()
+    }
+}
+
+impl Cipher for RailFenceCipher {
+    /// Reads the zigzag rail by rail.
+    fn encrypt(&self, clean_text: &str) -> String {
+        let clean_chars: Vec<char> = clean_text.chars().collect();
+        let rails: Vec<usize> = self.get_rails(clean_chars.len());
+        let mut ciphered_string: String = String::with_capacity(clean_text.len());
+        for rail in 0..self.get_rail_count(clean_chars.len()) {
+            for (position, clean_char) in clean_chars.iter().enumerate() {
+                if rails[position] == rail {
+                    ciphered_string.push(*clean_char);
+                }
+            }
+        }
+        ciphered_string
+    }
+    /// Fills the rails with the ciphered chars and reads them back in zigzag.
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        let ciphered_chars: Vec<char> = ciphered_text.chars().collect();
+        let rails: Vec<usize> = self.get_rails(ciphered_chars.len());
+        let mut rail_chars: Vec<std::iter::Copied<std::slice::Iter<char>>> = vec![];
+        let mut start: usize = 0;
+        for rail in 0..self.get_rail_count(ciphered_chars.len()) {
+            let rail_length: usize = rails.iter().filter(|the_rail| **the_rail == rail).count();
+            rail_chars.push(ciphered_chars[start..start + rail_length].iter().copied());
+            start += rail_length;
+        }
+        rails
+            .iter()
+            .filter_map(|rail| rail_chars[*rail].next())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_inverts_encrypt_for_every_rail_count() {
+        let clean_text: &str = "We are discovered, flee at once! 🦀 ñ";
+        for rails in [1, 2, 3, 5, 36, 37, MAX_RAILS] {
+            let cipher: RailFenceCipher = RailFenceCipher::new(&rails.to_string()).unwrap();
+            let ciphered_text: String = cipher.encrypt(clean_text);
+            assert_eq!(
+                cipher.decrypt(&ciphered_text),
+                clean_text,
+                "{} rails",
+                rails
+            );
+        }
+        assert_eq!(cipher_with("1").encrypt(clean_text), clean_text);
+        assert_eq!(cipher_with("5").encrypt(""), "");
+    }
+
+    #[test]
+    fn three_rails_match_the_classic_example() {
+        let cipher: RailFenceCipher = cipher_with("3");
+        assert_eq!(
+            cipher.encrypt("WEAREDISCOVEREDFLEEATONCE"),
+            "WECRLTEERDSOEEFEAOCAIVDEN"
+        );
+    }
+
+    #[test]
+    fn rail_counts_out_of_range_are_invalid_input() {
+        for key_string in ["0", "1001", "100000000000", "18446744073709551615", "two"] {
+            let error: std::io::Error = RailFenceCipher::new(key_string).err().unwrap();
+            assert_eq!(
+                error.kind(),
+                std::io::ErrorKind::InvalidInput,
+                "{}",
+                key_string
+            );
+        }
+    }
+
+    /// Creates a cipher with a valid key.
+    fn cipher_with(key_string: &str) -> RailFenceCipher {
+        RailFenceCipher::new(key_string).unwrap()
+    }
+}
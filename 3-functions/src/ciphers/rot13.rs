@@ -0,0 +1,61 @@
+//! The ROT13 cipher, a Caesar cipher with a fixed shift of 13.
+
+use super::caesar::caesar_cipher_char;
+use super::{Alphabet, CharStream, Cipher, Mode};
+
+const ROT13_SHIFT: usize = 13;
+
+/// A `struct` for the **ROT13 cipher**, that needs no key.
+///
+/// It always uses the English alphabet, the only one where 13 is half the letters.
+pub struct Rot13Cipher {
+    alphabet: Alphabet,
+}
+
+impl Rot13Cipher {
+    /// Creates a ROT13 cipher over the English alphabet.
+    pub fn new() -> Rot13Cipher {
+        Rot13Cipher {
+            alphabet: Alphabet::english(),
+        }
+    }
+}
+
+impl Default for Rot13Cipher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cipher for Rot13Cipher {
+    fn encrypt(&self, clean_text: &str) -> String {
+        clean_text
+            .chars()
+            .map(|clean_char| caesar_cipher_char(clean_char, ROT13_SHIFT, &self.alphabet))
+            .collect()
+    }
+    /// Shifting 13 twice goes around the 26 letters, so decrypting is encrypting again.
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        self.encrypt(ciphered_text)
+    }
+    fn char_stream(&self, _mode: Mode) -> Option<CharStream<'_>> {
+        Some(Box::new(|the_char| {
+            caesar_cipher_char(the_char, ROT13_SHIFT, &self.alphabet)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_inverts_encrypt() {
+        let cipher: Rot13Cipher = Rot13Cipher::new();
+        let clean_text: &str = "Why did the chicken cross the road? ñ 🦀";
+        let ciphered_text: String = cipher.encrypt(clean_text);
+        assert_eq!(ciphered_text, "Jul qvq gur puvpxra pebff gur ebnq? ñ 🦀");
+        assert_eq!(cipher.decrypt(&ciphered_text), clean_text);
+        assert_eq!(cipher.encrypt(""), "");
+    }
+}
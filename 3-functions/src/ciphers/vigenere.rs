@@ -0,0 +1,119 @@
+//! The Vigenère cipher, shifting each letter by the position of the next key letter.
+
+use super::caesar::{caesar_cipher_char, caesar_decipher_char};
+use super::{invalid_key, Alphabet, CharStream, Cipher, Mode};
+
+/// A `struct` for the **Vigenère cipher**, holding the shifts of the key letters and the alphabet.
+pub struct VigenereCipher {
+    shifts: Vec<usize>,
+    alphabet: Alphabet,
+}
+
+impl VigenereCipher {
+    /// Creates a Vigenère cipher, returning an error if the key has no letters of the alphabet.
+    pub fn new(key_string: &str, alphabet: &Alphabet) -> Result<VigenereCipher, std::io::Error> {
+        let shifts: Vec<usize> = get_vigenere_shifts(key_string, alphabet)?;
+        Ok(VigenereCipher {
+            shifts,
+            alphabet: alphabet.clone(),
+        })
+    }
+}
+
+impl Cipher for VigenereCipher {
+    fn encrypt(&self, clean_text: &str) -> String {
+        vigenere_cipher_text(clean_text, &self.shifts, &self.alphabet, Mode::Encrypt)
+    }
+    fn decrypt(&self, ciphered_text: &str) -> String {
+        vigenere_cipher_text(ciphered_text, &self.shifts, &self.alphabet, Mode::Decrypt)
+    }
+    /// Rotates the key over the letters only, like [`vigenere_cipher_text`] does.
+    fn char_stream(&self, mode: Mode) -> Option<CharStream<'_>> {
+        let mut key_index: usize = 0;
+        Some(Box::new(move |the_char| {
+            if self.alphabet.get_position(the_char).is_none() {
+                return the_char;
+            }
+            let shift: usize = self.shifts[key_index];
+            key_index = (key_index + 1) % self.shifts.len();
+            match mode {
+                Mode::Encrypt => caesar_cipher_char(the_char, shift, &self.alphabet),
+                Mode::Decrypt => caesar_decipher_char(the_char, shift, &self.alphabet),
+            }
+        }))
+    }
+}
+
+/// Encrypts or decrypts a string using the **Vigenère cipher**.
+///
+/// Each key letter is its position in the alphabet, so `a` and `A` shift by 0 and `z` by 25.
+/// Only the letters of the alphabet consume a key letter; other characters are left unchanged.
+/// ### Arguments
+/// * `text` - A string slice that holds the text to process.
+/// * `shifts` - A slice with the shift values of the key letters, never empty.
+/// * `alphabet` - The `Alphabet` with the letters to shift.
+/// * `mode` - A `Mode` to encrypt or decrypt the text.
+/// ### Returns
+/// * `String` - The processed text.
+/// ### Example
+/// ```
+/// use functions::ciphers::vigenere::vigenere_cipher_text;
+/// use functions::ciphers::{Alphabet, Mode};
+///
+/// let shifts: [usize; 5] = [11, 4, 12, 14, 13];
+/// let encrypted: String =
+///     vigenere_cipher_text("attack at dawn", &shifts, &Alphabet::english(), Mode::Encrypt);
+/// assert_eq!(encrypted, "lxfopv ef rnhr");
+/// ```
+pub fn vigenere_cipher_text(
+    text: &str,
+    shifts: &[usize],
+    alphabet: &Alphabet,
+    mode: Mode,
+) -> String {
+    let mut key_index: usize = 0;
+    let mut processed_string: String = String::with_capacity(text.len());
+    for the_char in text.chars() {
+        if alphabet.get_position(the_char).is_none() {
+            processed_string.push(the_char);
+            continue;
+        }
+        let shift: usize = shifts[key_index];
+        let processed_char: char = match mode {
+            Mode::Encrypt => caesar_cipher_char(the_char, shift, alphabet),
+            Mode::Decrypt => caesar_decipher_char(the_char, shift, alphabet),
+        };
+        processed_string.push(processed_char);
+        key_index = (key_index + 1) % shifts.len();
+    }
+    processed_string
+}
+
+/// Gets the Vigenère shift values of a key string.
+///
+/// Letters are mapped to their position in the alphabet, ignoring the case.
+/// Characters that are not letters of the alphabet are skipped.
+/// ### Arguments
+/// * `key_string` - A string slice that holds the key password.
+/// * `alphabet` - The `Alphabet` of the key letters.
+/// ### Returns
+/// * `Result<Vec<usize>, std::io::Error>` - The shifts, or an error if the key has no letters.
+fn get_vigenere_shifts(
+    key_string: &str,
+    alphabet: &Alphabet,
+) -> Result<Vec<usize>, std::io::Error> {
+    let shifts: Vec<usize> = key_string
+        .chars()
+        .filter_map(|key_char| {
+            alphabet
+                .get_position(key_char)
+                .map(|(position, _)| position)
+        })
+        .collect();
+    if shifts.is_empty() {
+        return Err(invalid_key(
+            "⚠️ - The Vigenère key must contain at least one letter of the alphabet.",
+        ));
+    }
+    Ok(shifts)
+}
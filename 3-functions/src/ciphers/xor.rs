@@ -0,0 +1,65 @@
+//! The XOR cipher, combining every byte with the next byte of a rotating key.
+
+use super::{invalid_key, ByteCipher};
+
+/// A `struct` for the **XOR cipher**, holding the bytes of the key.
+///
+/// Every byte of the input is combined, so it works with any file, not only texts.
+pub struct XorCipher {
+    key_bytes: Vec<u8>,
+}
+
+impl XorCipher {
+    /// Creates a XOR cipher, returning an error if the key is empty.
+    pub fn new(key_string: &str) -> Result<XorCipher, std::io::Error> {
+        if key_string.is_empty() {
+            return Err(invalid_key("⚠️ - The XOR key can not be empty."));
+        }
+        Ok(XorCipher {
+            key_bytes: key_string.as_bytes().to_vec(),
+        })
+    }
+}
+
+impl ByteCipher for XorCipher {
+    fn encrypt_bytes(&self, clean_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        let ciphered_bytes: Vec<u8> = clean_bytes
+            .iter()
+            .zip(self.key_bytes.iter().cycle())
+            .map(|(clean_byte, key_byte)| clean_byte ^ key_byte)
+            .collect();
+        Ok(ciphered_bytes)
+    }
+    /// Combining twice with the same key byte gives the original byte back.
+    fn decrypt_bytes(&self, ciphered_bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        self.encrypt_bytes(ciphered_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_inverts_encrypt_for_every_byte_value() {
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        for key_string in ["k", "clave", "ñ🦀\u{0}"] {
+            let cipher: XorCipher = XorCipher::new(key_string).unwrap();
+            let ciphered_bytes: Vec<u8> = cipher.encrypt_bytes(&all_bytes).unwrap();
+            assert_ne!(ciphered_bytes, all_bytes);
+            assert_eq!(cipher.decrypt_bytes(&ciphered_bytes).unwrap(), all_bytes);
+            assert!(cipher.encrypt_bytes(&[]).unwrap().is_empty());
+        }
+        let cipher: XorCipher = XorCipher::new("\u{f}").unwrap();
+        assert_eq!(
+            cipher.encrypt_bytes(&[0x00, 0xf0, 0xff]).unwrap(),
+            [0x0f, 0xff, 0xf0]
+        );
+    }
+
+    #[test]
+    fn an_empty_key_is_invalid_input() {
+        let error: std::io::Error = XorCipher::new("").err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
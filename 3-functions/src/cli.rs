@@ -0,0 +1,751 @@
+//! Command line arguments parsing for the cipher program.
+
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+use functions::ciphers::{
+    get_cipher_info, new_alphabet, Alphabet, CipherInfo, Mode, ALPHABETS, CIPHERS,
+    DEFAULT_KDF_ITERATIONS, MAX_KDF_ITERATIONS,
+};
+use functions::crypto::secret::{constant_time_eq, zeroize, SecretString};
+use functions::files::STANDARD_STREAM;
+use functions::pipeline::FileSettings;
+use functions::telegram::TextFormat;
+/// The device of the terminal, used to prompt for the key even when the input is piped.
+const TERMINAL: &str = "/dev/tty";
+/// The longest key line accepted from the prompt, in bytes, with its line break.
+const MAX_PROMPT_LENGTH: usize = 1024;
+/// The formats of the `--analyze` report.
+const REPORT_FORMATS: [&str; 2] = ["text", "json"];
+
+/// A `struct` that holds the command line arguments.
+/// It is used to store the _clean file name_ to read, the _key string_ used as the secret
+/// and the _KDF iterations_ to derive a key from it,
+/// the _mode_ to encrypt or decrypt the content, the _cipher name_ and _alphabet_ to use,
+/// the _output file name_ to write, whether to _force_ overwriting an existing output file,
+/// whether to write a _container_ with the settings, the _text format_ of a telegram,
+/// the _include_ and _exclude patterns_ to choose the files of an input directory
+/// and the number of _jobs_ to process them,
+/// whether to open the _interactive_ prompt,
+/// whether to _crack_ the key, the known _crib_ and the _crib file name_ of another ciphered file,
+/// whether to _analyze_ the letters and the _report format_,
+/// whether to be _quiet_ and whether to show the _help_.
+/// The key string is a `SecretString`, zeroized when dropped and never printed.
+#[derive(Clone)]
+pub struct CliArgs {
+    pub clean_file_name: String,
+    pub key_string: SecretString,
+    pub kdf_iterations: u32,
+    pub mode: Mode,
+    pub cipher_name: String,
+    pub alphabet: Alphabet,
+    pub output_file_name: String,
+    pub force: bool,
+    pub container: bool,
+    pub text_format: TextFormat,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub jobs: usize,
+    pub interactive: bool,
+    pub crack: bool,
+    pub crib: Option<String>,
+    pub crib_file_name: Option<String>,
+    pub analyze: bool,
+    pub report_format: String,
+    pub quiet: bool,
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Gets the settings of the library to encrypt or decrypt a file with these arguments.
+    pub fn file_settings(&self) -> FileSettings {
+        FileSettings {
+            cipher_name: self.cipher_name.clone(),
+            key_string: self.key_string.clone(),
+            kdf_iterations: self.kdf_iterations,
+            alphabet: self.alphabet.clone(),
+            mode: self.mode,
+            force: self.force,
+            container: self.container,
+            text_format: self.text_format,
+        }
+    }
+}
+
+/// An `enum` with the places the key can be read from.
+///
+/// The environment and the prompt keep the key out of the shell history and the process list.
+enum KeySource {
+    Text(String),
+    File(String),
+    Environment(String),
+    Prompt,
+}
+
+/// A `struct` that defines a command line flag, used to parse it and to print the help.
+struct Flag {
+    name: &'static str,
+    value_name: Option<&'static str>,
+    description: &'static str,
+}
+
+/// The definitions of all the flags accepted by the program.
+const FLAGS: [Flag; 28] = [
+    Flag {
+        name: "--encrypt",
+        value_name: None,
+        description: "Encrypts the input (default).",
+    },
+    Flag {
+        name: "--decrypt",
+        value_name: None,
+        description: "Decrypts the input.",
+    },
+    Flag {
+        name: "--interactive",
+        value_name: None,
+        description: "Opens a prompt to encrypt or decrypt lines as they are typed.",
+    },
+    Flag {
+        name: "--crack",
+        value_name: None,
+        description: "Recovers the caesar or vigenere key and decrypts the input.",
+    },
+    Flag {
+        name: "--crib",
+        value_name: Some("<text>"),
+        description: "Finds the caesar or vigenere keys that turn a known text into the input.",
+    },
+    Flag {
+        name: "--crib-with",
+        value_name: Some("<path>"),
+        description: "Another file with the same xor key, to drag the --crib over both.",
+    },
+    Flag {
+        name: "--analyze",
+        value_name: None,
+        description: "Reports the letter frequencies of the input instead.",
+    },
+    Flag {
+        name: "--format",
+        value_name: Some("<format>"),
+        description: "The format of the --analyze report: text (default) or json.",
+    },
+    Flag {
+        name: "--key",
+        value_name: Some("<key>"),
+        description: "The key string used as the secret.",
+    },
+    Flag {
+        name: "--key-file",
+        value_name: Some("<path>"),
+        description: "A file whose content is used as the key string.",
+    },
+    Flag {
+        name: "--key-env",
+        value_name: Some("<variable>"),
+        description: "An environment variable whose value is used as the key string.",
+    },
+    Flag {
+        name: "--key-prompt",
+        value_name: None,
+        description: "Asks for the key in the terminal, without showing it.",
+    },
+    Flag {
+        name: "--kdf-iterations",
+        value_name: Some("<count>"),
+        description:
+            "The PBKDF2 cost to derive the chacha20 key, 600000 by default and 6000000 at most.",
+    },
+    Flag {
+        name: "--cipher",
+        value_name: Some("<name>"),
+        description: "The cipher to use, caesar by default.",
+    },
+    Flag {
+        name: "--alphabet",
+        value_name: Some("<alphabet>"),
+        description: "An alphabet name, english by default, or its letters in order.",
+    },
+    Flag {
+        name: "--input",
+        value_name: Some("<path>"),
+        description: "The file or directory to read, - for the standard input (default).",
+    },
+    Flag {
+        name: "--output",
+        value_name: Some("<path>"),
+        description: "The file or directory to write, - for the standard output (default).",
+    },
+    Flag {
+        name: "--include",
+        value_name: Some("<pattern>"),
+        description: "Processes only the directory files matching, like *.txt or docs/**.",
+    },
+    Flag {
+        name: "--exclude",
+        value_name: Some("<pattern>"),
+        description: "Skips the directory files and folders matching, like *.png or .git.",
+    },
+    Flag {
+        name: "--jobs",
+        value_name: Some("<count>"),
+        description: "The files of a directory processed at once, one per CPU by default.",
+    },
+    Flag {
+        name: "--force",
+        value_name: None,
+        description: "Overwrites the output file if it already exists.",
+    },
+    Flag {
+        name: "--strip",
+        value_name: None,
+        description: "Removes anything but letters before encrypting, hiding words.",
+    },
+    Flag {
+        name: "--uppercase",
+        value_name: None,
+        description: "Turns the text to uppercase before encrypting.",
+    },
+    Flag {
+        name: "--group",
+        value_name: Some("<size>"),
+        description: "Writes the ciphered letters in groups, like 5, stripping the rest.",
+    },
+    Flag {
+        name: "--restore",
+        value_name: None,
+        description: "Joins the groups of a grouped text before decrypting it.",
+    },
+    Flag {
+        name: "--container",
+        value_name: None,
+        description: "Stores the cipher and alphabet with the output, detected to decrypt.",
+    },
+    Flag {
+        name: "--quiet",
+        value_name: None,
+        description: "Does not print progress messages.",
+    },
+    Flag {
+        name: "--help",
+        value_name: None,
+        description: "Prints this help.",
+    },
+];
+
+/// Prints the instructions for the program, generated from the flag definitions.
+pub fn print_instructions() {
+    println!("🔑 Encrypt and decrypt files using classical ciphers.");
+    println!("📘  The program reads the input and encrypts or decrypts it using the key.");
+    println!("📘  Progress messages are printed to the standard error.");
+    println!("📘  A directory input is processed file by file into the output directory.");
+    println!("📘  Flags:");
+    for flag in FLAGS.iter() {
+        let usage: String = format!("{} {}", flag.name, flag.value_name.unwrap_or_default());
+        println!("      {:<26} {}", usage, flag.description);
+    }
+    println!("📘  Ciphers:");
+    for cipher_info in CIPHERS.iter() {
+        let usage: &str = match (cipher_info.needs_key, cipher_info.binary) {
+            (_, true) => " (binary)",
+            (true, false) => "",
+            (false, false) => " (no key)",
+        };
+        println!(
+            "      {:<26} {}{}",
+            cipher_info.name, cipher_info.description, usage
+        );
+    }
+    println!("📘  Alphabets:");
+    for alphabet_info in ALPHABETS.iter() {
+        println!("      {:<26} {}", alphabet_info.name, alphabet_info.letters);
+    }
+    println!("🚀 Example: cargo run -- --input example.txt --key key --output encrypted.txt");
+    println!("🚀 Example: cargo run -- --decrypt --input encrypted.txt --key key");
+    println!(
+        "🚀 Example: cargo run -- --cipher xor --key key --input image.png --output image.xor"
+    );
+    println!("🚀 Example: cargo run -- --alphabet spanish --key eñe --input example.txt");
+    println!(
+        "🚀 Example: cargo run -- --container --cipher vigenere --key lemon --output secret.rslc"
+    );
+    println!(
+        "🚀 Example: cargo run -- --input docs --include *.txt --key key --output docs-encrypted"
+    );
+    println!(
+        "🚀 Example: cargo run -- --cipher vigenere --key lemon --uppercase --group 5 --input example.txt"
+    );
+    println!("🚀 Example: cargo run -- --interactive --cipher vigenere --key lemon");
+    println!("🚀 Example: cargo run -- --crack --cipher vigenere --input encrypted.txt");
+    println!(
+        "🚀 Example: cargo run -- --crib \"attack at\" --cipher vigenere --input encrypted.txt"
+    );
+    println!("🚀 Example: cargo run -- --analyze --format json --input encrypted.txt");
+}
+
+/// Reads the command line arguments.
+///
+/// The function reads the command line arguments and returns a `CliArgs` struct.
+/// ### Returns
+/// - A `Result` with the `CliArgs` struct if the args could be read.
+/// - Otherwise, if a flag is unknown, a value is missing, or the key is not provided,
+///   it returns an error message.
+pub fn read_args() -> Result<CliArgs, std::io::Error> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    parse_args(&args)
+}
+
+/// Parses a list of arguments, without the program name, into a `CliArgs` struct.
+///
+/// Flags with a value accept it as the next argument or after an equal sign: `--key=secret`.
+/// ### Arguments
+/// * `args` - A slice of strings with the arguments to parse.
+/// ### Returns
+/// - A `Result` with the `CliArgs` struct or an error message.
+pub fn parse_args(args: &[String]) -> Result<CliArgs, std::io::Error> {
+    let mut cli_args = CliArgs {
+        clean_file_name: STANDARD_STREAM.to_string(),
+        key_string: SecretString::default(),
+        kdf_iterations: DEFAULT_KDF_ITERATIONS,
+        mode: Mode::Encrypt,
+        cipher_name: CIPHERS[0].name.to_string(),
+        alphabet: Alphabet::english(),
+        output_file_name: STANDARD_STREAM.to_string(),
+        force: false,
+        container: false,
+        text_format: TextFormat::default(),
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        jobs: thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+        interactive: false,
+        crack: false,
+        crib: None,
+        crib_file_name: None,
+        analyze: false,
+        report_format: REPORT_FORMATS[0].to_string(),
+        quiet: false,
+        help: false,
+    };
+    let mut key_source: Option<KeySource> = None;
+    let mut alphabet_name: Option<String> = None;
+    let mut index: usize = 0;
+    while index < args.len() {
+        let (name, inline_value) = match args[index].split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (args[index].as_str(), None),
+        };
+        let flag: &Flag = match FLAGS.iter().find(|flag| flag.name == name) {
+            Some(flag) => flag,
+            None => return Err(invalid_input(&format!("⚠️ - Unknown argument {}.", name))),
+        };
+        let value: String = match (flag.value_name, inline_value) {
+            (None, None) => String::new(),
+            (None, Some(_)) => {
+                return Err(invalid_input(&format!("⚠️ - {} takes no value.", name)));
+            }
+            (Some(_), Some(value)) => value,
+            (Some(value_name), None) => {
+                index += 1;
+                match args.get(index) {
+                    Some(value) => value.clone(),
+                    None => {
+                        return Err(invalid_input(&format!(
+                            "⚠️ - Please provide a {} after {}.",
+                            value_name, name
+                        )));
+                    }
+                }
+            }
+        };
+        match flag.name {
+            "--encrypt" => cli_args.mode = Mode::Encrypt,
+            "--decrypt" => cli_args.mode = Mode::Decrypt,
+            "--interactive" => cli_args.interactive = true,
+            "--crack" => cli_args.crack = true,
+            "--crib" => cli_args.crib = Some(value),
+            "--crib-with" => cli_args.crib_file_name = Some(value),
+            "--analyze" => cli_args.analyze = true,
+            "--format" => cli_args.report_format = value,
+            "--key" | "--key-file" | "--key-env" | "--key-prompt" => {
+                if key_source.is_some() {
+                    return Err(invalid_input(
+                        "⚠️ - Please provide only one of --key, --key-file, --key-env or --key-prompt.",
+                    ));
+                }
+                key_source = Some(match flag.name {
+                    "--key" => KeySource::Text(value),
+                    "--key-file" => KeySource::File(value),
+                    "--key-env" => KeySource::Environment(value),
+                    _ => KeySource::Prompt,
+                });
+            }
+            "--kdf-iterations" => {
+                cli_args.kdf_iterations = match value.parse::<u32>() {
+                    Ok(kdf_iterations) if (1..=MAX_KDF_ITERATIONS).contains(&kdf_iterations) => {
+                        kdf_iterations
+                    }
+                    _ => {
+                        return Err(invalid_input(&format!(
+                            "⚠️ - The KDF iterations must be a number from 1 to {}.",
+                            MAX_KDF_ITERATIONS
+                        )));
+                    }
+                }
+            }
+            "--cipher" => cli_args.cipher_name = value,
+            "--alphabet" => alphabet_name = Some(value),
+            "--input" => cli_args.clean_file_name = value,
+            "--output" => cli_args.output_file_name = value,
+            "--include" => cli_args.include_patterns.push(value),
+            "--exclude" => cli_args.exclude_patterns.push(value),
+            "--jobs" => {
+                cli_args.jobs = match value.parse::<usize>() {
+                    Ok(jobs) if jobs > 0 => jobs,
+                    _ => {
+                        return Err(invalid_input("⚠️ - The jobs must be a positive number."));
+                    }
+                }
+            }
+            "--force" => cli_args.force = true,
+            "--strip" => cli_args.text_format.strip = true,
+            "--uppercase" => cli_args.text_format.uppercase = true,
+            "--group" => {
+                cli_args.text_format.group_size = match value.parse::<usize>() {
+                    Ok(group_size) if group_size > 0 => group_size,
+                    _ => {
+                        return Err(invalid_input(
+                            "⚠️ - The group size must be a positive number.",
+                        ));
+                    }
+                }
+            }
+            "--restore" => cli_args.text_format.restore = true,
+            "--container" => cli_args.container = true,
+            "--quiet" => cli_args.quiet = true,
+            _ => cli_args.help = true,
+        }
+        index += 1;
+    }
+    if cli_args.help {
+        return Ok(cli_args);
+    }
+    if !REPORT_FORMATS.contains(&cli_args.report_format.as_str()) {
+        return Err(invalid_input(&format!(
+            "⚠️ - Unknown format {}, use one of: {}.",
+            cli_args.report_format,
+            REPORT_FORMATS.join(", ")
+        )));
+    }
+    if Path::new(&cli_args.clean_file_name).is_dir() {
+        if cli_args.crack || cli_args.analyze || cli_args.crib.is_some() {
+            return Err(invalid_input(
+                "⚠️ - A directory can only be encrypted or decrypted, not attacked or analyzed.",
+            ));
+        }
+        if cli_args.output_file_name == STANDARD_STREAM {
+            return Err(invalid_input(
+                "⚠️ - Please provide an output directory with --output.",
+            ));
+        }
+    } else if !cli_args.include_patterns.is_empty() || !cli_args.exclude_patterns.is_empty() {
+        return Err(invalid_input(
+            "⚠️ - --include and --exclude need a directory as --input.",
+        ));
+    }
+    let cipher_info: &CipherInfo = match get_cipher_info(&cli_args.cipher_name) {
+        Some(cipher_info) => cipher_info,
+        None => {
+            let cipher_names: Vec<&str> = CIPHERS.iter().map(|info| info.name).collect();
+            return Err(invalid_input(&format!(
+                "⚠️ - Unknown cipher {}, use one of: {}.",
+                cli_args.cipher_name,
+                cipher_names.join(", ")
+            )));
+        }
+    };
+    if !cli_args.text_format.is_plain() {
+        if cipher_info.binary {
+            return Err(invalid_input(
+                "⚠️ - The text format options only work with the text ciphers.",
+            ));
+        }
+        let encrypting_options: bool = cli_args.text_format.strip
+            || cli_args.text_format.uppercase
+            || cli_args.text_format.group_size > 0;
+        if encrypting_options && cli_args.mode == Mode::Decrypt {
+            return Err(invalid_input(
+                "⚠️ - --strip, --uppercase and --group only work when encrypting, use --restore.",
+            ));
+        }
+        if cli_args.text_format.restore && cli_args.mode == Mode::Encrypt {
+            return Err(invalid_input("⚠️ - --restore only works when decrypting."));
+        }
+    }
+    if cli_args.interactive && cipher_info.binary {
+        return Err(invalid_input(
+            "⚠️ - The interactive mode only works with the text ciphers.",
+        ));
+    }
+    if cli_args.crack && cli_args.cipher_name != "caesar" && cli_args.cipher_name != "vigenere" {
+        return Err(invalid_input(
+            "⚠️ - Only the caesar and vigenere ciphers can be cracked.",
+        ));
+    }
+    if cli_args.crib.is_some() {
+        let crib_with_xor: bool = cli_args.cipher_name == "xor";
+        if !crib_with_xor && cli_args.cipher_name != "caesar" && cli_args.cipher_name != "vigenere"
+        {
+            return Err(invalid_input(
+                "⚠️ - Only the caesar, vigenere and xor ciphers can be attacked with a crib.",
+            ));
+        }
+        if crib_with_xor != cli_args.crib_file_name.is_some() {
+            return Err(invalid_input(
+                "⚠️ - Please use --crib-with with the xor cipher, and only with it.",
+            ));
+        }
+    } else if cli_args.crib_file_name.is_some() {
+        return Err(invalid_input("⚠️ - --crib-with needs a --crib."));
+    }
+    if let Some(alphabet_name) = alphabet_name {
+        if (cli_args.crack || cli_args.crib.is_some()) && alphabet_name != ALPHABETS[0].name {
+            return Err(invalid_input(
+                "⚠️ - Only texts with the english alphabet can be cracked.",
+            ));
+        }
+        cli_args.alphabet = new_alphabet(&alphabet_name)?;
+    }
+    let needs_key: bool = cipher_info.needs_key
+        && !cli_args.interactive
+        && !cli_args.crack
+        && !cli_args.analyze
+        && cli_args.crib.is_none();
+    let encrypting: bool = cli_args.mode == Mode::Encrypt && needs_key;
+    cli_args.key_string = match key_source {
+        Some(KeySource::Text(key_string)) => SecretString::new(key_string),
+        Some(KeySource::File(key_file_name)) => read_key_file(&key_file_name)?,
+        Some(KeySource::Environment(variable_name)) => read_key_environment(&variable_name)?,
+        Some(KeySource::Prompt) => read_key_prompt(encrypting)?,
+        None if !needs_key => SecretString::default(),
+        None => {
+            return Err(invalid_input(
+                "⚠️ - Please provide the key with --key, --key-file, --key-env or --key-prompt.",
+            ));
+        }
+    };
+    if needs_key && cli_args.key_string.is_empty() {
+        return Err(invalid_input("⚠️ - The key can not be empty."));
+    }
+    Ok(cli_args)
+}
+
+/// Reads a key string from a file, ignoring the trailing line break.
+fn read_key_file(key_file_name: &str) -> Result<SecretString, std::io::Error> {
+    let content: String = fs::read_to_string(key_file_name)?;
+    Ok(remove_line_break(content))
+}
+
+/// Reads a key string from an environment variable.
+fn read_key_environment(variable_name: &str) -> Result<SecretString, std::io::Error> {
+    env::var(variable_name).map(SecretString::new).map_err(|_| {
+        invalid_input(&format!(
+            "⚠️ - The environment variable {} is not set.",
+            variable_name
+        ))
+    })
+}
+
+/// Reads a key string from the terminal without echoing it.
+///
+/// When encrypting, the key is asked twice, so a typo does not lock the content forever.
+/// ### Arguments
+/// * `confirm` - A bool to ask for the key a second time.
+/// ### Returns
+/// * `Result<SecretString, std::io::Error>` - The key, or an error if there is no terminal
+///   or the two keys do not match.
+fn read_key_prompt(confirm: bool) -> Result<SecretString, std::io::Error> {
+    let key_string: SecretString = prompt_hidden("🔑 Key: ")?;
+    if confirm {
+        let repeated_key_string: SecretString = prompt_hidden("🔑 Repeat the key: ")?;
+        if !constant_time_eq(
+            repeated_key_string.expose().as_bytes(),
+            key_string.expose().as_bytes(),
+        ) {
+            return Err(invalid_input("⚠️ - The keys do not match."));
+        }
+    }
+    Ok(key_string)
+}
+
+/// Prints a prompt in the terminal and reads a line with the echo turned off.
+fn prompt_hidden(prompt: &str) -> Result<SecretString, std::io::Error> {
+    let mut terminal: fs::File = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(TERMINAL)
+        .map_err(|_| invalid_input("⚠️ - --key-prompt needs a terminal."))?;
+    terminal.write_all(prompt.as_bytes())?;
+    set_terminal_echo(false)?;
+    let read_result: Result<SecretString, std::io::Error> = read_hidden_line(&mut terminal);
+    set_terminal_echo(true)?;
+    terminal.write_all(b"\n")?;
+    read_result
+}
+
+/// Reads a line straight into a buffer of a fixed size, without the line break.
+///
+/// Nothing is buffered elsewhere and the buffer never grows, so wiping it at the end
+/// leaves no copy of the key behind.
+fn read_hidden_line(reader: &mut dyn Read) -> Result<SecretString, std::io::Error> {
+    let mut line_bytes: Vec<u8> = vec![0; MAX_PROMPT_LENGTH];
+    let mut line_length: usize = 0;
+    let read_result: Result<(), std::io::Error> = loop {
+        if line_bytes[..line_length].contains(&b'\n') {
+            break Ok(());
+        }
+        if line_length == line_bytes.len() {
+            break Err(invalid_input(&format!(
+                "⚠️ - The key can not be longer than {} bytes.",
+                MAX_PROMPT_LENGTH - 1
+            )));
+        }
+        match reader.read(&mut line_bytes[line_length..]) {
+            Ok(0) => break Ok(()),
+            Ok(read_length) => line_length += read_length,
+            Err(error) => break Err(error),
+        }
+    };
+    let key_length: usize = line_bytes[..line_length]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .unwrap_or(line_length);
+    let key_bytes: &[u8] = line_bytes[..key_length]
+        .strip_suffix(b"\r")
+        .unwrap_or(&line_bytes[..key_length]);
+    let key_result: Result<SecretString, std::io::Error> =
+        read_result.and_then(|_| match std::str::from_utf8(key_bytes) {
+            Ok(key_text) => Ok(SecretString::new(key_text.to_string())),
+            Err(_) => Err(invalid_input("⚠️ - The key is not valid UTF-8 text.")),
+        });
+    zeroize(&mut line_bytes);
+    key_result
+}
+
+/// Removes the trailing line break of a key in place, so no trimmed copy is left behind.
+fn remove_line_break(mut key_line: String) -> SecretString {
+    let key_length: usize = key_line.trim_end_matches(['\r', '\n']).len();
+    key_line.truncate(key_length);
+    SecretString::new(key_line)
+}
+
+/// Turns the echo of the terminal on or off with the `stty` command.
+fn set_terminal_echo(enabled: bool) -> Result<(), std::io::Error> {
+    let echo_setting: &str = if enabled { "echo" } else { "-echo" };
+    let status = Command::new("stty")
+        .arg(echo_setting)
+        .stdin(fs::File::open(TERMINAL)?)
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(
+            "⚠️ - Can not turn off the echo of the terminal.",
+        ));
+    }
+    Ok(())
+}
+
+/// Creates an error for an invalid command line argument.
+fn invalid_input(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses some arguments given as string slices.
+    fn parse(args: &[&str]) -> Result<CliArgs, std::io::Error> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn values_are_read_after_a_space_or_an_equal_sign() {
+        let cli_args: CliArgs = parse(&[
+            "--cipher=vigenere",
+            "--key",
+            "lemon",
+            "--output=secret.txt",
+            "--decrypt",
+            "--jobs=3",
+        ])
+        .unwrap();
+        assert_eq!(cli_args.cipher_name, "vigenere");
+        assert_eq!(cli_args.key_string.expose(), "lemon");
+        assert_eq!(cli_args.output_file_name, "secret.txt");
+        assert!(cli_args.mode == Mode::Decrypt);
+        assert_eq!(cli_args.jobs, 3);
+        assert_eq!(cli_args.clean_file_name, STANDARD_STREAM);
+        let cli_args: CliArgs = parse(&["--key=a=b"]).unwrap();
+        assert_eq!(cli_args.key_string.expose(), "a=b");
+    }
+
+    #[test]
+    fn usage_errors_are_invalid_input() {
+        let invalid_args: [&[&str]; 10] = [
+            &["--key", "one", "--key-file", "key.txt"],
+            &["--key=one", "--key-prompt"],
+            &["--key-env", "KEY", "--key", "one"],
+            &["--unknown", "--key", "one"],
+            &["-k", "one"],
+            &["--force=yes", "--key", "one"],
+            &["--key"],
+            &["--cipher", "--key", "one"],
+            &["--cipher", "enigma", "--key", "one"],
+            &["--jobs=0", "--key", "one"],
+        ];
+        for args in invalid_args {
+            let error: std::io::Error = parse(args).err().unwrap();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput, "{:?}", args);
+        }
+        assert!(parse(&[]).is_err());
+    }
+
+    #[test]
+    fn help_is_returned_before_the_other_checks() {
+        let cli_args: CliArgs = parse(&["--cipher", "enigma", "--help"]).unwrap();
+        assert!(cli_args.help);
+    }
+
+    #[test]
+    fn hidden_lines_are_read_without_the_line_break() {
+        for (typed_line, expected_key) in [
+            ("clave secreta\n", "clave secreta"),
+            ("ñandú\r\nignored", "ñandú"),
+            ("no line break", "no line break"),
+            ("\n", ""),
+        ] {
+            let key_string: SecretString = read_hidden_line(&mut typed_line.as_bytes()).unwrap();
+            assert_eq!(key_string.expose(), expected_key);
+        }
+    }
+
+    #[test]
+    fn hidden_lines_too_long_or_not_utf8_are_invalid_input() {
+        let long_line: Vec<u8> = vec![b'k'; MAX_PROMPT_LENGTH + 1];
+        let invalid_line: &[u8] = &[0xff, 0xfe, b'\n'];
+        for typed_line in [&long_line[..], invalid_line] {
+            let error: std::io::Error = read_hidden_line(&mut &typed_line[..]).err().unwrap();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        }
+        let longest_line: Vec<u8> = vec![b'k'; MAX_PROMPT_LENGTH];
+        assert!(read_hidden_line(&mut &longest_line[..]).is_err());
+        let mut accepted_line: Vec<u8> = vec![b'k'; MAX_PROMPT_LENGTH - 1];
+        accepted_line.push(b'\n');
+        assert!(read_hidden_line(&mut &accepted_line[..]).is_ok());
+    }
+}
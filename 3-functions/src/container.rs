@@ -0,0 +1,388 @@
+//! A versioned container that stores the cipher settings with the ciphered content.
+
+use std::io::{Cursor, Read, Write};
+
+use crate::ciphers::{Alphabet, CIPHERS};
+use crate::crypto::secret::constant_time_eq;
+use crate::crypto::sha256::{Sha256, DIGEST_SIZE};
+
+/// The bytes that start every container.
+///
+/// The first one can not start a UTF-8 char, so no ciphered text is taken for a container.
+const MAGIC: &[u8; 5] = b"\x89RSLC";
+/// The version of the container layout, increased when it changes.
+const VERSION: u8 = 1;
+/// The id of the parameter with the letters of the alphabet.
+const ALPHABET_PARAMETER: u8 = 1;
+/// The number of bytes read at once from the content of a container.
+const READ_SIZE: usize = 64 * 1024;
+
+/// A `struct` with the settings needed to decrypt the content of a container.
+///
+/// The binary ciphers have no alphabet; the key derivation settings of the chacha20 cipher,
+/// like the salt and the nonce, travel in its own authenticated header inside the content.
+pub struct ContainerSettings {
+    /// The name of the cipher used to encrypt the content.
+    pub cipher_name: String,
+    /// The alphabet of the text ciphers, or None for the binary ones.
+    pub alphabet: Option<Alphabet>,
+}
+
+/// An `enum` with what was found at the start of an input.
+pub enum DetectedInput {
+    /// A container, with its settings and a reader of the ciphered content
+    /// that verifies the checksum at the end.
+    Container(ContainerSettings, Box<dyn Read>),
+    /// Any other content, with the input rewound to its start.
+    Plain(Box<dyn Read>),
+}
+
+/// A `struct` that writes to another writer while hashing everything written.
+struct ChecksumWriter<'a> {
+    inner: &'a mut dyn Write,
+    hash: Sha256,
+}
+
+impl Write for ChecksumWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        let written_length: usize = self.inner.write(bytes)?;
+        self.hash.update(&bytes[..written_length]);
+        Ok(written_length)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A `struct` that reads the content of a container while hashing it.
+///
+/// The last bytes read are held back until the input ends, because they may be the checksum,
+/// which is then compared with the hash of everything before it.
+struct ChecksumReader {
+    inner: Box<dyn Read>,
+    hash: Sha256,
+    pending: Vec<u8>,
+    finished: bool,
+}
+
+impl ChecksumReader {
+    /// Compares the held back checksum with the hash of the container.
+    fn verify(&mut self) -> std::io::Result<()> {
+        if self.pending.len() < DIGEST_SIZE {
+            return Err(invalid_container("⚠️ - The container is truncated."));
+        }
+        let checksum: [u8; DIGEST_SIZE] = std::mem::take(&mut self.hash).finalize();
+        if !constant_time_eq(&checksum, &self.pending) {
+            return Err(invalid_container(
+                "⚠️ - The container checksum does not match: the file is corrupted.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Read for ChecksumReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if buffer.is_empty() || self.finished {
+            return Ok(0);
+        }
+        while self.pending.len() <= DIGEST_SIZE {
+            let pending_length: usize = self.pending.len();
+            self.pending.resize(pending_length + READ_SIZE, 0);
+            let read_length: usize = match self.inner.read(&mut self.pending[pending_length..]) {
+                Ok(read_length) => read_length,
+                Err(error) => {
+                    self.pending.truncate(pending_length);
+                    return Err(error);
+                }
+            };
+            self.pending.truncate(pending_length + read_length);
+            if read_length == 0 {
+                self.finished = true;
+                self.verify()?;
+                return Ok(0);
+            }
+        }
+        let released_length: usize = (self.pending.len() - DIGEST_SIZE).min(buffer.len());
+        buffer[..released_length].copy_from_slice(&self.pending[..released_length]);
+        self.hash.update(&self.pending[..released_length]);
+        self.pending.drain(..released_length);
+        Ok(released_length)
+    }
+}
+
+/// Writes a container: the header with the settings, the content and a checksum.
+///
+/// The layout is the magic bytes, the version, the cipher id, the number of parameters,
+/// each parameter as an id, a 2 bytes length and its value, the content and, at the end,
+/// the SHA-256 of everything before it, so the content can be streamed.
+/// ### Arguments
+/// * `writer` - The output to write the container.
+/// * `settings` - The `ContainerSettings` used to encrypt the content.
+/// * `write_content` - A function that writes the ciphered content.
+/// ### Returns
+/// * `Result<T, std::io::Error>` - The result of the function, or the first error.
+/// ### Example
+/// ```
+/// use functions::ciphers::Alphabet;
+/// use functions::container::{detect_container, write_container, ContainerSettings, DetectedInput};
+/// use std::io::{Read, Write};
+///
+/// let settings = ContainerSettings {
+///     cipher_name: "vigenere".to_string(),
+///     alphabet: Some(Alphabet::english()),
+/// };
+/// let mut bytes: Vec<u8> = vec![];
+/// write_container(&mut bytes, &settings, |writer| writer.write_all(b"lxfopv")).unwrap();
+/// match detect_container(Box::new(std::io::Cursor::new(bytes))).unwrap() {
+///     DetectedInput::Container(settings, mut content) => {
+///         assert_eq!(settings.cipher_name, "vigenere");
+///         let mut content_bytes: Vec<u8> = vec![];
+///         content.read_to_end(&mut content_bytes).unwrap();
+///         assert_eq!(content_bytes, b"lxfopv");
+///     }
+///     DetectedInput::Plain(_) => panic!("the container was not detected"),
+/// }
+/// ```
+pub fn write_container<T>(
+    writer: &mut dyn Write,
+    settings: &ContainerSettings,
+    write_content: impl FnOnce(&mut dyn Write) -> Result<T, std::io::Error>,
+) -> Result<T, std::io::Error> {
+    let cipher_id: u8 = match CIPHERS
+        .iter()
+        .find(|info| info.name == settings.cipher_name)
+    {
+        Some(cipher_info) => cipher_info.id,
+        None => return Err(invalid_container("⚠️ - The cipher has no container id.")),
+    };
+    let mut parameters: Vec<(u8, Vec<u8>)> = vec![];
+    if let Some(alphabet) = &settings.alphabet {
+        parameters.push((ALPHABET_PARAMETER, alphabet.letters().into_bytes()));
+    }
+    let mut header: Vec<u8> = MAGIC.to_vec();
+    header.push(VERSION);
+    header.push(cipher_id);
+    header.push(parameters.len() as u8);
+    for (parameter_id, value) in parameters.iter() {
+        header.push(*parameter_id);
+        header.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        header.extend_from_slice(value);
+    }
+    let mut checksum_writer: ChecksumWriter = ChecksumWriter {
+        inner: writer,
+        hash: Sha256::new(),
+    };
+    checksum_writer.write_all(&header)?;
+    let result: T = write_content(&mut checksum_writer)?;
+    let checksum: [u8; DIGEST_SIZE] = checksum_writer.hash.finalize();
+    writer.write_all(&checksum)?;
+    Ok(result)
+}
+
+/// Reads the header of a container if the input starts with its magic bytes.
+///
+/// The content is not loaded: it is read from the returned reader, that holds back the
+/// trailing checksum and verifies it when the end is reached, failing with an error
+/// if the container is corrupted. So the whole content must be read before trusting it;
+/// the output files are written atomically, so a corrupted container leaves no output file.
+/// ### Arguments
+/// * `input` - The input to read.
+/// ### Returns
+/// * `Result<DetectedInput, std::io::Error>` - The container or the untouched input,
+///   or an error if the container header is truncated, of another version or uses an unknown cipher.
+pub fn detect_container(mut input: Box<dyn Read>) -> Result<DetectedInput, std::io::Error> {
+    let mut magic_bytes: Vec<u8> = Vec::with_capacity(MAGIC.len());
+    input
+        .by_ref()
+        .take(MAGIC.len() as u64)
+        .read_to_end(&mut magic_bytes)?;
+    if magic_bytes != MAGIC {
+        return Ok(DetectedInput::Plain(Box::new(
+            Cursor::new(magic_bytes).chain(input),
+        )));
+    }
+    let mut reader: ChecksumReader = ChecksumReader {
+        inner: input,
+        hash: Sha256::new(),
+        pending: Vec::with_capacity(READ_SIZE + DIGEST_SIZE),
+        finished: false,
+    };
+    reader.hash.update(MAGIC);
+    let [version, cipher_id, parameter_count] = read_header_bytes::<3>(&mut reader)?;
+    if version != VERSION {
+        return Err(invalid_container(&format!(
+            "⚠️ - The container version {} is not supported.",
+            version
+        )));
+    }
+    let cipher_name: &str = match CIPHERS.iter().find(|info| info.id == cipher_id) {
+        Some(cipher_info) => cipher_info.name,
+        None => {
+            return Err(invalid_container(&format!(
+                "⚠️ - The container cipher id {} is unknown.",
+                cipher_id
+            )));
+        }
+    };
+    let mut alphabet: Option<Alphabet> = None;
+    for _ in 0..parameter_count {
+        let [parameter_id, length_high, length_low] = read_header_bytes::<3>(&mut reader)?;
+        let mut value: Vec<u8> = vec![0; u16::from_be_bytes([length_high, length_low]) as usize];
+        reader.read_exact(&mut value).map_err(truncated_header)?;
+        match parameter_id {
+            ALPHABET_PARAMETER => {
+                let letters: &str = std::str::from_utf8(&value).map_err(|_| {
+                    invalid_container("⚠️ - The container alphabet is not valid UTF-8.")
+                })?;
+                alphabet = Some(Alphabet::new(letters)?);
+            }
+            _ => {
+                return Err(invalid_container(&format!(
+                    "⚠️ - The container parameter id {} is unknown.",
+                    parameter_id
+                )));
+            }
+        }
+    }
+    let settings: ContainerSettings = ContainerSettings {
+        cipher_name: cipher_name.to_string(),
+        alphabet,
+    };
+    Ok(DetectedInput::Container(settings, Box::new(reader)))
+}
+
+/// Reads some bytes of the container header, failing if it is truncated.
+fn read_header_bytes<const N: usize>(
+    reader: &mut ChecksumReader,
+) -> Result<[u8; N], std::io::Error> {
+    let mut bytes: [u8; N] = [0; N];
+    reader.read_exact(&mut bytes).map_err(truncated_header)?;
+    Ok(bytes)
+}
+
+/// Turns the end of the input while reading the header into a truncated container error.
+fn truncated_header(error: std::io::Error) -> std::io::Error {
+    if error.kind() == std::io::ErrorKind::UnexpectedEof {
+        invalid_container("⚠️ - The container is truncated.")
+    } else {
+        error
+    }
+}
+
+/// Creates an error for a container that can not be read.
+fn invalid_container(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a vigenere container with the english alphabet around some content.
+    fn new_container(content: &[u8]) -> Vec<u8> {
+        let settings: ContainerSettings = ContainerSettings {
+            cipher_name: "vigenere".to_string(),
+            alphabet: Some(Alphabet::english()),
+        };
+        let mut bytes: Vec<u8> = vec![];
+        write_container(&mut bytes, &settings, |writer| writer.write_all(content)).unwrap();
+        bytes
+    }
+
+    /// Detects a container and reads its content a few bytes at a time.
+    fn read_container(bytes: Vec<u8>) -> Result<(ContainerSettings, Vec<u8>), std::io::Error> {
+        match detect_container(Box::new(Cursor::new(bytes)))? {
+            DetectedInput::Container(settings, mut content) => {
+                let mut content_bytes: Vec<u8> = vec![];
+                let mut buffer: [u8; 3] = [0; 3];
+                loop {
+                    let read_length: usize = content.read(&mut buffer)?;
+                    if read_length == 0 {
+                        return Ok((settings, content_bytes));
+                    }
+                    content_bytes.extend_from_slice(&buffer[..read_length]);
+                }
+            }
+            DetectedInput::Plain(_) => panic!("the container was not detected"),
+        }
+    }
+
+    /// Gets the message of the error of reading a container.
+    fn read_error(bytes: Vec<u8>) -> String {
+        match read_container(bytes) {
+            Ok(_) => panic!("the container was read"),
+            Err(error) => {
+                assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+                error.to_string()
+            }
+        }
+    }
+
+    #[test]
+    fn the_content_is_streamed_back_with_the_settings() {
+        let content: Vec<u8> = (0..100_000).map(|index| (index % 251) as u8).collect();
+        let (settings, content_bytes) = read_container(new_container(&content)).unwrap();
+        assert_eq!(settings.cipher_name, "vigenere");
+        assert_eq!(
+            settings.alphabet.unwrap().letters(),
+            Alphabet::english().letters()
+        );
+        assert_eq!(content_bytes, content);
+        assert!(read_container(new_container(b"")).unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn a_text_starting_like_the_magic_is_plain() {
+        let text: &[u8] = b"RSLC axeeh phkew";
+        match detect_container(Box::new(Cursor::new(text.to_vec()))).unwrap() {
+            DetectedInput::Plain(mut input) => {
+                let mut input_bytes: Vec<u8> = vec![];
+                input.read_to_end(&mut input_bytes).unwrap();
+                assert_eq!(input_bytes, text);
+            }
+            DetectedInput::Container(..) => panic!("a plain text was taken for a container"),
+        }
+    }
+
+    #[test]
+    fn truncated_containers_are_rejected() {
+        let bytes: Vec<u8> = new_container(b"lxfopv");
+        for length in MAGIC.len()..bytes.len() {
+            let message: String = read_error(bytes[..length].to_vec());
+            assert!(
+                message.contains("truncated") || message.contains("checksum"),
+                "{} bytes: {}",
+                length,
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn foreign_versions_and_unknown_ciphers_are_rejected() {
+        let mut bytes: Vec<u8> = new_container(b"lxfopv");
+        bytes[MAGIC.len()] = VERSION + 1;
+        assert!(read_error(bytes).contains("version 2"));
+        let mut bytes: Vec<u8> = new_container(b"lxfopv");
+        bytes[MAGIC.len() + 1] = 200;
+        assert!(read_error(bytes).contains("cipher id 200"));
+    }
+
+    #[test]
+    fn every_flipped_bit_after_the_magic_is_detected() {
+        let bytes: Vec<u8> = new_container(b"lxfopv ef rnhr");
+        for position in MAGIC.len()..bytes.len() {
+            for bit in 0..8 {
+                let mut flipped_bytes: Vec<u8> = bytes.clone();
+                flipped_bytes[position] ^= 1 << bit;
+                assert!(
+                    read_container(flipped_bytes).is_err(),
+                    "bit {} of byte {}",
+                    bit,
+                    position
+                );
+            }
+        }
+    }
+}
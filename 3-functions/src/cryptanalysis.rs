@@ -0,0 +1,522 @@
+//! Cryptanalysis of the Caesar and Vigenère ciphers, recovering the key without knowing it.
+
+use std::collections::HashMap;
+
+use crate::ciphers::{get_base_code_option, CASE_LENGTH};
+
+/// The relative frequency of each letter in English texts, from `a` to `z`.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+/// The longest Vigenère key length tried when cracking.
+const MAX_KEY_LENGTH: usize = 20;
+/// The length of the repeated sequences searched by the Kasiski test.
+const KASISKI_SEQUENCE_LENGTH: usize = 3;
+/// The index of coincidence of English texts.
+pub const ENGLISH_COINCIDENCE: f64 = 0.0667;
+/// The ratio to the best index of coincidence for a key length to be a candidate.
+const IOC_CANDIDATE_RATIO: f64 = 0.9;
+
+/// A `struct` with the ranking of the 26 shifts of a Caesar ciphered text.
+pub struct CaesarAnalysis {
+    /// The shifts and their chi-squared distance to English, from the most likely.
+    pub ranking: Vec<(u8, f64)>,
+}
+
+/// A `struct` with the steps and result of cracking a Vigenère ciphered text.
+pub struct VigenereAnalysis {
+    /// The factors of the distances between repeated sequences and how many times they appear.
+    pub kasiski_votes: Vec<(usize, usize)>,
+    /// The average index of coincidence of the columns for every key length tried.
+    pub coincidences: Vec<(usize, f64)>,
+    /// The estimated length of the key.
+    pub key_length: usize,
+    /// The most likely key, in lowercase letters.
+    pub key_string: String,
+}
+
+/// Gets the positions in the alphabet, from 0 to 25, of the ASCII letters of a text.
+/// ### Arguments
+/// * `text` - A string slice with the text.
+/// ### Returns
+/// * `Vec<u8>` - The positions of the letters, ignoring any other character.
+pub fn get_letter_positions(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter_map(|the_char| {
+            get_base_code_option(the_char).map(|base_case_code| the_char as u8 - base_case_code)
+        })
+        .collect()
+}
+
+/// Counts how many times each letter position appears.
+pub fn count_letters(positions: &[u8]) -> [usize; 26] {
+    let mut counts: [usize; 26] = [0; 26];
+    for position in positions {
+        counts[*position as usize] += 1;
+    }
+    counts
+}
+
+/// Calculates the chi-squared distance between some letter counts and the English frequencies.
+///
+/// The lower the distance, the more the counts look like an English text.
+/// ### Arguments
+/// * `counts` - The number of times each letter appears.
+/// ### Returns
+/// * `f64` - The chi-squared distance.
+pub fn chi_squared(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return f64::MAX;
+    }
+    counts
+        .iter()
+        .zip(ENGLISH_FREQUENCIES.iter())
+        .map(|(count, frequency)| {
+            let expected: f64 = frequency * total as f64;
+            (*count as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Calculates the index of coincidence: the probability that two random letters are equal.
+///
+/// English texts are close to 0.067, while random letters are close to 0.038.
+/// ### Arguments
+/// * `counts` - The number of times each letter appears.
+/// ### Returns
+/// * `f64` - The index of coincidence, or 0 with less than two letters.
+pub fn index_of_coincidence(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+        return 0.0;
+    }
+    let pairs: usize = counts
+        .iter()
+        .map(|count| count * count.saturating_sub(1))
+        .sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+/// Gets the chi-squared distance of a list of letters decrypted with a shift.
+fn get_shift_distance(positions: &[u8], shift: u8) -> f64 {
+    let shifted: Vec<u8> = positions
+        .iter()
+        .map(|position| (position + CASE_LENGTH - shift) % CASE_LENGTH)
+        .collect();
+    chi_squared(&count_letters(&shifted))
+}
+
+/// Ranks the 26 shifts of a text by the chi-squared distance of its decryption to English.
+/// ### Arguments
+/// * `text` - A string slice with the ciphered text.
+/// ### Returns
+/// * `CaesarAnalysis` - The shifts from the most to the least likely.
+pub fn crack_caesar(text: &str) -> CaesarAnalysis {
+    let positions: Vec<u8> = get_letter_positions(text);
+    let mut ranking: Vec<(u8, f64)> = (0..CASE_LENGTH)
+        .map(|shift| (shift, get_shift_distance(&positions, shift)))
+        .collect();
+    ranking.sort_by(|first, second| first.1.total_cmp(&second.1));
+    CaesarAnalysis { ranking }
+}
+
+/// Gets the key char that the Caesar cipher of this program turns into a shift.
+///
+/// The Caesar cipher shifts by the char code, so the lowercase letter with the same
+/// code modulo 26 is returned.
+pub fn get_caesar_key_char(shift: u8) -> char {
+    ('a'..='z')
+        .find(|key_char| *key_char as u8 % CASE_LENGTH == shift)
+        .unwrap_or('a')
+}
+
+/// Counts the factors of the distances between repeated sequences of letters (Kasiski test).
+///
+/// The key length is likely to be one of the most repeated factors.
+/// ### Arguments
+/// * `positions` - The letter positions of the ciphered text.
+/// ### Returns
+/// * `Vec<(usize, usize)>` - The factors and their votes, from the most voted.
+pub fn kasiski_test(positions: &[u8]) -> Vec<(usize, usize)> {
+    let mut last_seen: HashMap<&[u8], usize> = HashMap::new();
+    let mut votes: HashMap<usize, usize> = HashMap::new();
+    for start in 0..positions.len().saturating_sub(KASISKI_SEQUENCE_LENGTH - 1) {
+        let sequence: &[u8] = &positions[start..start + KASISKI_SEQUENCE_LENGTH];
+        if let Some(previous_start) = last_seen.insert(sequence, start) {
+            let distance: usize = start - previous_start;
+            for factor in 2..=MAX_KEY_LENGTH.min(distance) {
+                if distance.is_multiple_of(factor) {
+                    *votes.entry(factor).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut kasiski_votes: Vec<(usize, usize)> = votes.into_iter().collect();
+    kasiski_votes.sort_by(|first, second| second.1.cmp(&first.1).then(first.0.cmp(&second.0)));
+    kasiski_votes
+}
+
+/// Splits the letter positions in columns, one for each letter of a key of the given length.
+fn get_columns(positions: &[u8], key_length: usize) -> Vec<Vec<u8>> {
+    let mut columns: Vec<Vec<u8>> = vec![vec![]; key_length];
+    for (index, position) in positions.iter().enumerate() {
+        columns[index % key_length].push(*position);
+    }
+    columns
+}
+
+/// Estimates the key length and recovers the key of a Vigenère ciphered text.
+///
+/// The key length is the shortest one whose columns have an index of coincidence close
+/// to the best one, preferring the lengths voted by the Kasiski test.
+/// When the whole text already has an index of coincidence close to English,
+/// it is not polyalphabetic.
+/// Then each column is solved as a Caesar cipher with the chi-squared distance.
+/// ### Arguments
+/// * `text` - A string slice with the ciphered text.
+/// ### Returns
+/// * `VigenereAnalysis` - The steps of the analysis and the most likely key.
+pub fn crack_vigenere(text: &str) -> VigenereAnalysis {
+    let positions: Vec<u8> = get_letter_positions(text);
+    let kasiski_votes: Vec<(usize, usize)> = kasiski_test(&positions);
+    let max_length: usize = MAX_KEY_LENGTH.min(positions.len() / 2).max(1);
+    let coincidences: Vec<(usize, f64)> = (1..=max_length)
+        .map(|key_length| {
+            let columns: Vec<Vec<u8>> = get_columns(&positions, key_length);
+            let total: f64 = columns
+                .iter()
+                .map(|column| index_of_coincidence(&count_letters(column)))
+                .sum();
+            (key_length, total / key_length as f64)
+        })
+        .collect();
+    let best_coincidence: f64 = coincidences
+        .iter()
+        .map(|(_, coincidence)| *coincidence)
+        .fold(0.0, f64::max);
+    let candidates: Vec<usize> = coincidences
+        .iter()
+        .filter(|(_, coincidence)| *coincidence >= best_coincidence * IOC_CANDIDATE_RATIO)
+        .map(|(key_length, _)| *key_length)
+        .collect();
+    let monoalphabetic: bool = coincidences
+        .first()
+        .is_some_and(|(_, coincidence)| *coincidence >= ENGLISH_COINCIDENCE * IOC_CANDIDATE_RATIO);
+    let key_length: usize = if monoalphabetic || candidates.first() == Some(&1) {
+        1
+    } else {
+        candidates
+            .iter()
+            .find(|key_length| {
+                kasiski_votes
+                    .iter()
+                    .any(|(factor, _)| factor == *key_length)
+            })
+            .or(candidates.first())
+            .copied()
+            .unwrap_or(1)
+    };
+    let key_string: String = get_columns(&positions, key_length)
+        .iter()
+        .map(|column| {
+            let shift: u8 = (0..CASE_LENGTH)
+                .min_by(|first, second| {
+                    get_shift_distance(column, *first)
+                        .total_cmp(&get_shift_distance(column, *second))
+                })
+                .unwrap_or(0);
+            (b'a' + shift) as char
+        })
+        .collect();
+    VigenereAnalysis {
+        kasiski_votes,
+        coincidences,
+        key_length,
+        key_string,
+    }
+}
+
+/// A `struct` with the key material found by placing a known plaintext (a crib) at an offset.
+pub struct CribMatch {
+    /// The offset of the crib, counted in letters for Vigenère and in chars for Caesar.
+    pub offset: usize,
+    /// The key chars under the crib, with `?` where the crib gives no information.
+    pub key_fragment: String,
+    /// The shortest key that repeats to give the fragment, aligned to the start of the text,
+    /// or None if the fragment does not repeat.
+    pub key_string: Option<String>,
+}
+
+/// A `struct` with the bytes found by dragging a crib over two texts ciphered with the same key.
+pub struct DragMatch {
+    /// The offset of the crib in the first text, in bytes.
+    pub offset: usize,
+    /// The bytes of the second text at the same offset, if the crib is right.
+    pub other_fragment: Vec<u8>,
+    /// The bytes of the key at the same offset, if the crib is right.
+    pub key_fragment: Vec<u8>,
+    /// The ratio of letters and spaces of the other fragment, higher for plain text.
+    pub score: f64,
+}
+
+/// Recovers the Vigenère key under a known plaintext at every offset of a ciphered text.
+///
+/// Every letter of the crib gives a key letter; when the key letters repeat inside the crib,
+/// the crib is consistent at that offset and the whole key is known.
+/// ### Arguments
+/// * `ciphered_text` - A string slice with the ciphered text.
+/// * `crib` - A string slice with a known part of the clean text; only its letters are used.
+/// ### Returns
+/// * `Vec<CribMatch>` - The key material at every offset, in order.
+/// ### Example
+/// ```
+/// use functions::cryptanalysis::crib_vigenere;
+///
+/// let matches = crib_vigenere("lxfopv ef rnhr", "attackatda");
+/// assert_eq!(matches[0].key_string.as_deref(), Some("lemon"));
+/// ```
+pub fn crib_vigenere(ciphered_text: &str, crib: &str) -> Vec<CribMatch> {
+    let ciphered_positions: Vec<u8> = get_letter_positions(ciphered_text);
+    let crib_positions: Vec<u8> = get_letter_positions(crib);
+    if crib_positions.is_empty() || crib_positions.len() > ciphered_positions.len() {
+        return vec![];
+    }
+    (0..=ciphered_positions.len() - crib_positions.len())
+        .map(|offset| {
+            let shifts: Vec<Option<u8>> = crib_positions
+                .iter()
+                .zip(&ciphered_positions[offset..])
+                .map(|(crib_position, ciphered_position)| {
+                    Some((ciphered_position + CASE_LENGTH - crib_position) % CASE_LENGTH)
+                })
+                .collect();
+            new_crib_match(offset, &shifts, |shift| (b'a' + shift) as char)
+        })
+        .collect()
+}
+
+/// Recovers the Caesar key under a known plaintext at every offset of a ciphered text.
+///
+/// The Caesar cipher of this program moves to the next key char after every char,
+/// so a single char key gives the same shift everywhere. The chars that are not letters
+/// are not ciphered, so they must match exactly and give no key information.
+/// ### Arguments
+/// * `ciphered_text` - A string slice with the ciphered text.
+/// * `crib` - A string slice with a known part of the clean text.
+/// ### Returns
+/// * `Vec<CribMatch>` - The key material at every offset where the crib fits, in order.
+/// ### Example
+/// ```
+/// use functions::cryptanalysis::crib_caesar;
+///
+/// let matches = crib_caesar("Dro aesmu lbygx pyh", "quick");
+/// let found: Vec<&str> = matches.iter().filter_map(|found| found.key_string.as_deref()).collect();
+/// assert_eq!(found, ["r"]);
+/// ```
+pub fn crib_caesar(ciphered_text: &str, crib: &str) -> Vec<CribMatch> {
+    let ciphered_chars: Vec<char> = ciphered_text.chars().collect();
+    let crib_chars: Vec<char> = crib.chars().collect();
+    if crib_chars.is_empty() || crib_chars.len() > ciphered_chars.len() {
+        return vec![];
+    }
+    (0..=ciphered_chars.len() - crib_chars.len())
+        .filter_map(|offset| {
+            let shifts: Option<Vec<Option<u8>>> = crib_chars
+                .iter()
+                .zip(&ciphered_chars[offset..])
+                .map(|(crib_char, ciphered_char)| {
+                    match (
+                        get_base_code_option(*crib_char),
+                        get_base_code_option(*ciphered_char),
+                    ) {
+                        (Some(crib_base), Some(ciphered_base)) if crib_base == ciphered_base => {
+                            let crib_position: u8 = *crib_char as u8 - crib_base;
+                            let ciphered_position: u8 = *ciphered_char as u8 - ciphered_base;
+                            Some(Some(
+                                (ciphered_position + CASE_LENGTH - crib_position) % CASE_LENGTH,
+                            ))
+                        }
+                        (None, None) if crib_char == ciphered_char => Some(None),
+                        _ => None,
+                    }
+                })
+                .collect();
+            shifts.map(|shifts| new_crib_match(offset, &shifts, get_caesar_key_char))
+        })
+        .collect()
+}
+
+/// Creates a crib match from the shifts under the crib, looking for the shortest period.
+///
+/// A period is accepted when the fragment holds it at least twice,
+/// and every pair of known shifts one period apart is equal.
+fn new_crib_match(offset: usize, shifts: &[Option<u8>], to_key_char: fn(u8) -> char) -> CribMatch {
+    let key_fragment: String = shifts
+        .iter()
+        .map(|shift| shift.map_or('?', to_key_char))
+        .collect();
+    let key_period: Option<usize> = (1..=shifts.len() / 2).find(|period| {
+        let pairs: Vec<(u8, u8)> = shifts
+            .iter()
+            .zip(&shifts[*period..])
+            .filter_map(|(first, second)| first.zip(*second))
+            .collect();
+        !pairs.is_empty() && pairs.iter().all(|(first, second)| first == second)
+    });
+    let key_string: Option<String> = key_period.map(|period| {
+        (0..period)
+            .map(|key_index| {
+                let first_index: usize = (key_index + period - offset % period) % period;
+                shifts
+                    .iter()
+                    .skip(first_index)
+                    .step_by(period)
+                    .find_map(|shift| *shift)
+                    .map_or('?', to_key_char)
+            })
+            .collect()
+    });
+    CribMatch {
+        offset,
+        key_fragment,
+        key_string,
+    }
+}
+
+/// Drags a crib over two texts ciphered with the same rotating XOR key.
+///
+/// XORing both ciphered texts removes the key, so where the crib is in the first text,
+/// the same bytes of the second text appear in clear. Only offsets where they are
+/// printable are kept, from the most to the least likely.
+/// ### Arguments
+/// * `first_bytes` - The bytes of the first ciphered text.
+/// * `second_bytes` - The bytes of the second ciphered text, with the key at the same position.
+/// * `crib` - The bytes of a known or guessed part of the first clean text.
+/// ### Returns
+/// * `Vec<DragMatch>` - The printable matches, sorted by score.
+/// ### Example
+/// ```
+/// use functions::cryptanalysis::drag_crib;
+///
+/// let key: &[u8] = b"k3y";
+/// let cipher = |text: &[u8]| -> Vec<u8> {
+///     text.iter().zip(key.iter().cycle()).map(|(byte, key_byte)| byte ^ key_byte).collect()
+/// };
+/// let first: Vec<u8> = cipher(b"meet me at the old bridge");
+/// let second: Vec<u8> = cipher(b"bring the money tomorrow!");
+/// let matches = drag_crib(&first, &second, b"the old");
+/// assert_eq!(matches[0].offset, 11);
+/// assert_eq!(matches[0].other_fragment, b"oney to");
+/// ```
+pub fn drag_crib(first_bytes: &[u8], second_bytes: &[u8], crib: &[u8]) -> Vec<DragMatch> {
+    let common_length: usize = first_bytes.len().min(second_bytes.len());
+    if crib.is_empty() || crib.len() > common_length {
+        return vec![];
+    }
+    let mut matches: Vec<DragMatch> = (0..=common_length - crib.len())
+        .filter_map(|offset| {
+            let range = offset..offset + crib.len();
+            let other_fragment: Vec<u8> = first_bytes[range.clone()]
+                .iter()
+                .zip(&second_bytes[range.clone()])
+                .zip(crib)
+                .map(|((first_byte, second_byte), crib_byte)| first_byte ^ second_byte ^ crib_byte)
+                .collect();
+            let printable: bool = other_fragment
+                .iter()
+                .all(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace());
+            if !printable {
+                return None;
+            }
+            let key_fragment: Vec<u8> = first_bytes[range]
+                .iter()
+                .zip(crib)
+                .map(|(first_byte, crib_byte)| first_byte ^ crib_byte)
+                .collect();
+            let text_count: usize = other_fragment
+                .iter()
+                .filter(|byte| byte.is_ascii_alphabetic() || **byte == b' ')
+                .count();
+            Some(DragMatch {
+                offset,
+                score: text_count as f64 / crib.len() as f64,
+                other_fragment,
+                key_fragment,
+            })
+        })
+        .collect();
+    matches.sort_by(|first, second| {
+        second
+            .score
+            .total_cmp(&first.score)
+            .then(first.offset.cmp(&second.offset))
+    });
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{new_cipher, Alphabet, Mode};
+
+    /// An English text long enough for the statistics to point to the key.
+    const ENGLISH_TEXT: &str = "It was the best of times, it was the worst of times, \
+it was the age of wisdom, it was the age of foolishness, it was the epoch of belief, \
+it was the epoch of incredulity, it was the season of Light, it was the season of Darkness, \
+it was the spring of hope, it was the winter of despair, we had everything before us, \
+we had nothing before us, we were all going direct to Heaven, we were all going direct \
+the other way. In short, the period was so far like the present period, that some of its \
+noisiest authorities insisted on its being received, for good or for evil, in the \
+superlative degree of comparison only. There were a king with a large jaw and a queen \
+with a plain face, on the throne of England; there were a king with a large jaw and a \
+queen with a fair face, on the throne of France.";
+
+    /// Encrypts the English text with a cipher of this program.
+    fn encrypt_with(name: &str, key_string: &str) -> String {
+        new_cipher(name, key_string, &Alphabet::english())
+            .unwrap()
+            .apply(ENGLISH_TEXT, Mode::Encrypt)
+    }
+
+    #[test]
+    fn crack_vigenere_recovers_keys_of_several_lengths() {
+        for key_string in ["key", "lemon", "crypto", "dickens"] {
+            let analysis: VigenereAnalysis = crack_vigenere(&encrypt_with("vigenere", key_string));
+            assert_eq!(analysis.key_length, key_string.len(), "{}", key_string);
+            assert_eq!(analysis.key_string, key_string);
+        }
+    }
+
+    #[test]
+    fn crack_vigenere_finds_a_single_shift_in_monoalphabetic_texts() {
+        let analysis: VigenereAnalysis = crack_vigenere(ENGLISH_TEXT);
+        assert_eq!(analysis.key_length, 1);
+        assert_eq!(analysis.key_string, "a");
+    }
+
+    #[test]
+    fn crack_caesar_ranks_the_used_shift_first() {
+        for shift in [1, 3, 13, 25] {
+            let key_char: char = get_caesar_key_char(shift);
+            let analysis: CaesarAnalysis =
+                crack_caesar(&encrypt_with("caesar", &key_char.to_string()));
+            assert_eq!(analysis.ranking[0].0, shift);
+            assert_eq!(analysis.ranking.len(), 26);
+        }
+    }
+
+    #[test]
+    fn index_of_coincidence_separates_english_from_flat_letters() {
+        let english: f64 =
+            index_of_coincidence(&count_letters(&get_letter_positions(ENGLISH_TEXT)));
+        assert!((0.060..0.075).contains(&english), "{}", english);
+        assert_eq!(index_of_coincidence(&[1; 26]), 0.0);
+        assert_eq!(index_of_coincidence(&[0; 26]), 0.0);
+        let mut counts: [usize; 26] = [0; 26];
+        counts[0] = 4;
+        assert_eq!(index_of_coincidence(&counts), 1.0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Modern cryptography written from scratch: the ChaCha20-Poly1305 AEAD and PBKDF2.
+
+pub mod chacha20;
+pub mod poly1305;
+pub mod secret;
+pub mod sha256;
+
+use std::fs;
+use std::io::Read;
+
+use chacha20::{chacha20_block, chacha20_xor, KEY_SIZE, NONCE_SIZE};
+use poly1305::{poly1305_tag, TAG_SIZE};
+use secret::{constant_time_eq, zeroize};
+
+/// The file read to get secure random bytes from the operating system.
+const RANDOM_SOURCE: &str = "/dev/urandom";
+
+/// Encrypts and authenticates a message with **ChaCha20-Poly1305**, as defined in RFC 8439.
+/// ### Arguments
+/// * `key` - The 32 bytes of the key.
+/// * `nonce` - The 12 bytes of the nonce, never repeated with the same key.
+/// * `associated_data` - Bytes that are authenticated but not encrypted, like a header.
+/// * `clean_bytes` - The bytes to encrypt.
+/// ### Returns
+/// * `Result<Vec<u8>, std::io::Error>` - The ciphered bytes followed by the 16 bytes of the tag,
+///   or an error if they are too long for one nonce.
+/// ### Example
+/// ```
+/// use functions::crypto::{open, seal};
+///
+/// let (key, nonce): ([u8; 32], [u8; 12]) = ([7; 32], [1; 12]);
+/// let mut sealed: Vec<u8> = seal(&key, &nonce, b"header", b"secret").unwrap();
+/// assert_eq!(open(&key, &nonce, b"header", &sealed).unwrap(), b"secret");
+/// sealed[0] ^= 1;
+/// assert!(open(&key, &nonce, b"header", &sealed).is_none());
+/// ```
+pub fn seal(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    associated_data: &[u8],
+    clean_bytes: &[u8],
+) -> Result<Vec<u8>, std::io::Error> {
+    let mut sealed_bytes: Vec<u8> = clean_bytes.to_vec();
+    chacha20_xor(key, 1, nonce, &mut sealed_bytes)?;
+    let tag: [u8; TAG_SIZE] = get_tag(key, nonce, associated_data, &sealed_bytes);
+    sealed_bytes.extend_from_slice(&tag);
+    Ok(sealed_bytes)
+}
+
+/// Verifies and decrypts a message sealed with [`seal`].
+///
+/// The tag is compared in constant time, so its bytes can not be guessed one by one.
+/// ### Arguments
+/// * `key` - The 32 bytes of the key.
+/// * `nonce` - The 12 bytes of the nonce used to seal.
+/// * `associated_data` - The same associated data used to seal.
+/// * `sealed_bytes` - The ciphered bytes followed by the tag.
+/// ### Returns
+/// * `Option<Vec<u8>>` - The clean bytes, or None if the tag does not match,
+///   because the key is wrong or the bytes were modified,
+///   or if they are too long to have been sealed with one nonce.
+pub fn open(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    associated_data: &[u8],
+    sealed_bytes: &[u8],
+) -> Option<Vec<u8>> {
+    let ciphered_length: usize = sealed_bytes.len().checked_sub(TAG_SIZE)?;
+    let (ciphered_bytes, tag) = sealed_bytes.split_at(ciphered_length);
+    if !constant_time_eq(&get_tag(key, nonce, associated_data, ciphered_bytes), tag) {
+        return None;
+    }
+    let mut clean_bytes: Vec<u8> = ciphered_bytes.to_vec();
+    chacha20_xor(key, 1, nonce, &mut clean_bytes).ok()?;
+    Some(clean_bytes)
+}
+
+/// Computes the Poly1305 tag of the associated data and the ciphered bytes.
+///
+/// The one-time Poly1305 key is the first block of the ChaCha20 key stream,
+/// overwritten with zeros once the tag is computed.
+fn get_tag(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    associated_data: &[u8],
+    ciphered_bytes: &[u8],
+) -> [u8; TAG_SIZE] {
+    let mut key_block: [u8; 64] = chacha20_block(key, 0, nonce);
+    let mut poly_key: [u8; poly1305::KEY_SIZE] = [0; poly1305::KEY_SIZE];
+    poly_key.copy_from_slice(&key_block[..poly1305::KEY_SIZE]);
+    let mut message: Vec<u8> =
+        Vec::with_capacity(associated_data.len() + ciphered_bytes.len() + 48);
+    for part in [associated_data, ciphered_bytes] {
+        message.extend_from_slice(part);
+        message.resize(message.len().next_multiple_of(16), 0);
+    }
+    message.extend_from_slice(&(associated_data.len() as u64).to_le_bytes());
+    message.extend_from_slice(&(ciphered_bytes.len() as u64).to_le_bytes());
+    let tag: [u8; TAG_SIZE] = poly1305_tag(&message, &poly_key);
+    zeroize(&mut key_block);
+    zeroize(&mut poly_key);
+    tag
+}
+
+/// Fills a buffer with secure random bytes from the operating system.
+/// ### Arguments
+/// * `bytes` - The buffer to fill.
+/// ### Returns
+/// * `Result<(), std::io::Error>` - An error if there is no random source.
+pub fn fill_random(bytes: &mut [u8]) -> Result<(), std::io::Error> {
+    fs::File::open(RANDOM_SOURCE)
+        .and_then(|mut random_source| random_source.read_exact(bytes))
+        .map_err(|error| {
+            std::io::Error::new(
+                error.kind(),
+                format!(
+                    "⚠️ - Can not read random bytes from {}: {}",
+                    RANDOM_SOURCE, error
+                ),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The plain text of the RFC 8439 encryption and AEAD test vectors.
+    pub(super) const SUNSCREEN_TEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: \
+If I could offer you only one tip for the future, sunscreen would be it.";
+
+    /// Decodes the hexadecimal text of a test vector, ignoring the spaces.
+    pub(super) fn decode_hex(hex: &str) -> Vec<u8> {
+        let digits: Vec<u8> = hex
+            .bytes()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect();
+        digits
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn seal_matches_the_rfc_8439_aead_vector() {
+        let key: [u8; KEY_SIZE] = std::array::from_fn(|index| 0x80 + index as u8);
+        let nonce: [u8; NONCE_SIZE] = decode_hex("07000000 40414243 44454647").try_into().unwrap();
+        let associated_data: Vec<u8> = decode_hex("50515253 c0c1c2c3 c4c5c6c7");
+        let expected_bytes: Vec<u8> = decode_hex(
+            "d31a8d34648e60db7b86afbc53ef7ec2 a4aded51296e08fea9e2b5a736ee62d6
+             3dbea45e8ca9671282fafb69da92728b 1a71de0a9e060b2905d6a5b67ecd3b36
+             92ddbd7f2d778b8c9803aee328091b58 fab324e4fad675945585808b4831d7bc
+             3ff4def08e4b7a9de576d26586cec64b 6116
+             1ae10b594f09e26a7e902ecbd0600691",
+        );
+        let sealed_bytes: Vec<u8> = seal(&key, &nonce, &associated_data, SUNSCREEN_TEXT).unwrap();
+        assert_eq!(sealed_bytes, expected_bytes);
+        assert_eq!(
+            open(&key, &nonce, &associated_data, &sealed_bytes).unwrap(),
+            SUNSCREEN_TEXT
+        );
+    }
+}
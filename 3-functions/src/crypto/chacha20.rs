@@ -0,0 +1,148 @@
+//! The ChaCha20 stream cipher, as defined in RFC 8439.
+
+/// The size in bytes of a ChaCha20 key.
+pub const KEY_SIZE: usize = 32;
+/// The size in bytes of a ChaCha20 nonce.
+pub const NONCE_SIZE: usize = 12;
+/// The size in bytes of a block of key stream.
+const BLOCK_SIZE: usize = 64;
+/// The constant words `expand 32-byte k` that start every state.
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// Mixes four words of the state, the building block of every round.
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// Computes a block of 64 bytes of key stream.
+/// ### Arguments
+/// * `key` - The 32 bytes of the key.
+/// * `counter` - The number of the block.
+/// * `nonce` - The 12 bytes of the nonce, never repeated with the same key.
+/// ### Returns
+/// * `[u8; 64]` - The key stream block.
+pub fn chacha20_block(key: &[u8; KEY_SIZE], counter: u32, nonce: &[u8; NONCE_SIZE]) -> [u8; 64] {
+    let mut initial_state: [u32; 16] = [0; 16];
+    initial_state[..4].copy_from_slice(&CONSTANTS);
+    for index in 0..8 {
+        initial_state[4 + index] = read_u32(&key[index * 4..]);
+    }
+    initial_state[12] = counter;
+    for index in 0..3 {
+        initial_state[13 + index] = read_u32(&nonce[index * 4..]);
+    }
+    let mut state: [u32; 16] = initial_state;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+    let mut block: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+    for (index, word) in state.iter().enumerate() {
+        let sum: u32 = word.wrapping_add(initial_state[index]);
+        block[index * 4..index * 4 + 4].copy_from_slice(&sum.to_le_bytes());
+    }
+    block
+}
+
+/// Encrypts or decrypts some bytes in place, combining them with the key stream.
+///
+/// The block counter is 32 bits, so a nonce can encrypt at most 256 GiB;
+/// longer inputs are refused instead of repeating the key stream.
+/// ### Arguments
+/// * `key` - The 32 bytes of the key.
+/// * `initial_counter` - The number of the first block of key stream.
+/// * `nonce` - The 12 bytes of the nonce.
+/// * `bytes` - The bytes to process.
+/// ### Returns
+/// * `Result<(), std::io::Error>` - An error if the bytes need more blocks than the counter has left.
+pub fn chacha20_xor(
+    key: &[u8; KEY_SIZE],
+    initial_counter: u32,
+    nonce: &[u8; NONCE_SIZE],
+    bytes: &mut [u8],
+) -> Result<(), std::io::Error> {
+    let block_count: u64 = (bytes.len() as u64).div_ceil(BLOCK_SIZE as u64);
+    if block_count > (1 << 32) - initial_counter as u64 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "⚠️ - The input is too long to be encrypted with a single chacha20 nonce.",
+        ));
+    }
+    for (index, chunk) in bytes.chunks_mut(BLOCK_SIZE).enumerate() {
+        let counter: u32 = initial_counter + index as u32;
+        let key_stream: [u8; BLOCK_SIZE] = chacha20_block(key, counter, nonce);
+        for (byte, key_byte) in chunk.iter_mut().zip(key_stream.iter()) {
+            *byte ^= key_byte;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a little-endian word from the first 4 bytes of a slice.
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::tests::{decode_hex, SUNSCREEN_TEXT};
+
+    #[test]
+    fn the_counter_never_wraps_around() {
+        let (key, nonce): ([u8; KEY_SIZE], [u8; NONCE_SIZE]) = ([1; KEY_SIZE], [2; NONCE_SIZE]);
+        let mut last_block: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+        assert!(chacha20_xor(&key, u32::MAX, &nonce, &mut last_block).is_ok());
+        assert_eq!(last_block, chacha20_block(&key, u32::MAX, &nonce));
+        let mut one_byte_more: [u8; BLOCK_SIZE + 1] = [0; BLOCK_SIZE + 1];
+        let error: std::io::Error =
+            chacha20_xor(&key, u32::MAX, &nonce, &mut one_byte_more).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(one_byte_more, [0; BLOCK_SIZE + 1]);
+    }
+
+    /// The key 00 01 02 ... 1f used by the RFC 8439 test vectors.
+    fn rfc_key() -> [u8; KEY_SIZE] {
+        std::array::from_fn(|index| index as u8)
+    }
+
+    #[test]
+    fn block_matches_the_rfc_8439_vector() {
+        let nonce: [u8; NONCE_SIZE] = decode_hex("00000009 0000004a 00000000").try_into().unwrap();
+        let expected_block: Vec<u8> = decode_hex(
+            "10f1e7e4d13b5915500fdd1fa32071c4 c7d1f4c733c068030422aa9ac3d46c4e
+             d2826446079faa0914c2d705d98b02a2 b5129cd1de164eb9cbd083e8a2503c4e",
+        );
+        assert_eq!(
+            chacha20_block(&rfc_key(), 1, &nonce).to_vec(),
+            expected_block
+        );
+    }
+
+    #[test]
+    fn encryption_matches_the_rfc_8439_vector() {
+        let nonce: [u8; NONCE_SIZE] = decode_hex("00000000 0000004a 00000000").try_into().unwrap();
+        let expected_bytes: Vec<u8> = decode_hex(
+            "6e2e359a2568f98041ba0728dd0d6981 e97e7aec1d4360c20a27afccfd9fae0b
+             f91b65c5524733ab8f593dabcd62b357 1639d624e65152ab8f530c359f0861d8
+             07ca0dbf500d6a6156a38e088a22b65e 52bc514d16ccf806818ce91ab7793736
+             5af90bbf74a35be6b40b8eedf2785e42 874d",
+        );
+        let mut bytes: Vec<u8> = SUNSCREEN_TEXT.to_vec();
+        chacha20_xor(&rfc_key(), 1, &nonce, &mut bytes).unwrap();
+        assert_eq!(bytes, expected_bytes);
+    }
+}
@@ -0,0 +1,122 @@
+//! The Poly1305 one-time authenticator, as defined in RFC 8439.
+
+/// The size in bytes of a Poly1305 key.
+pub const KEY_SIZE: usize = 32;
+/// The size in bytes of a Poly1305 tag.
+pub const TAG_SIZE: usize = 16;
+/// The mask of the 26 bits kept in every limb.
+const LIMB_MASK: u64 = 0x3ff_ffff;
+
+/// Computes the tag of a message, using 5 limbs of 26 bits for the numbers modulo 2^130 - 5.
+///
+/// The key must be used for one message only.
+/// ### Arguments
+/// * `message` - The bytes to authenticate.
+/// * `key` - The 32 bytes of the one-time key: `r` and `s`.
+/// ### Returns
+/// * `[u8; 16]` - The authentication tag.
+pub fn poly1305_tag(message: &[u8], key: &[u8; KEY_SIZE]) -> [u8; TAG_SIZE] {
+    let r: [u64; 5] = [
+        read_u32(&key[0..]) & 0x3ff_ffff,
+        (read_u32(&key[3..]) >> 2) & 0x3ff_ff03,
+        (read_u32(&key[6..]) >> 4) & 0x3ff_c0ff,
+        (read_u32(&key[9..]) >> 6) & 0x3f0_3fff,
+        (read_u32(&key[12..]) >> 8) & 0x00f_ffff,
+    ];
+    let s: [u64; 4] = [1, 2, 3, 4].map(|index| read_u32(&key[index * 4 + 12..]));
+    let mut h: [u64; 5] = [0; 5];
+    for chunk in message.chunks(TAG_SIZE) {
+        let mut block: [u8; 17] = [0; 17];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()] = 1;
+        h[0] += read_u32(&block[0..]) & LIMB_MASK;
+        h[1] += (read_u32(&block[3..]) >> 2) & LIMB_MASK;
+        h[2] += (read_u32(&block[6..]) >> 4) & LIMB_MASK;
+        h[3] += (read_u32(&block[9..]) >> 6) & LIMB_MASK;
+        h[4] += (read_u32(&block[12..]) >> 8) | ((block[16] as u64) << 24);
+        h = multiply(&h, &r);
+    }
+    let h: [u64; 5] = reduce(h);
+    let words: [u64; 4] = [
+        (h[0] | (h[1] << 26)) & 0xffff_ffff,
+        ((h[1] >> 6) | (h[2] << 20)) & 0xffff_ffff,
+        ((h[2] >> 12) | (h[3] << 14)) & 0xffff_ffff,
+        ((h[3] >> 18) | (h[4] << 8)) & 0xffff_ffff,
+    ];
+    let mut tag: [u8; TAG_SIZE] = [0; TAG_SIZE];
+    let mut carry: u64 = 0;
+    for index in 0..4 {
+        let sum: u64 = words[index] + s[index] + carry;
+        tag[index * 4..index * 4 + 4].copy_from_slice(&(sum as u32).to_le_bytes());
+        carry = sum >> 32;
+    }
+    tag
+}
+
+/// Multiplies the accumulator by `r` modulo 2^130 - 5, leaving the limbs partially reduced.
+fn multiply(h: &[u64; 5], r: &[u64; 5]) -> [u64; 5] {
+    let s: [u64; 5] = r.map(|limb| limb * 5);
+    let mut d: [u64; 5] = [
+        h[0] * r[0] + h[1] * s[4] + h[2] * s[3] + h[3] * s[2] + h[4] * s[1],
+        h[0] * r[1] + h[1] * r[0] + h[2] * s[4] + h[3] * s[3] + h[4] * s[2],
+        h[0] * r[2] + h[1] * r[1] + h[2] * r[0] + h[3] * s[4] + h[4] * s[3],
+        h[0] * r[3] + h[1] * r[2] + h[2] * r[1] + h[3] * r[0] + h[4] * s[4],
+        h[0] * r[4] + h[1] * r[3] + h[2] * r[2] + h[3] * r[1] + h[4] * r[0],
+    ];
+    for index in 0..4 {
+        d[index + 1] += d[index] >> 26;
+        d[index] &= LIMB_MASK;
+    }
+    let carry: u64 = d[4] >> 26;
+    d[4] &= LIMB_MASK;
+    d[0] += carry * 5;
+    d[1] += d[0] >> 26;
+    d[0] &= LIMB_MASK;
+    d
+}
+
+/// Fully reduces the accumulator modulo 2^130 - 5, without branching on its value.
+fn reduce(mut h: [u64; 5]) -> [u64; 5] {
+    for index in 1..5 {
+        h[index] += h[index - 1] >> 26;
+        h[index - 1] &= LIMB_MASK;
+    }
+    h[0] += (h[4] >> 26) * 5;
+    h[4] &= LIMB_MASK;
+    h[1] += h[0] >> 26;
+    h[0] &= LIMB_MASK;
+    let mut g: [u64; 5] = [0; 5];
+    let mut carry: u64 = 5;
+    for index in 0..4 {
+        g[index] = h[index] + carry;
+        carry = g[index] >> 26;
+        g[index] &= LIMB_MASK;
+    }
+    g[4] = (h[4] + carry).wrapping_sub(1 << 26);
+    let mask: u64 = (g[4] >> 63).wrapping_sub(1);
+    for index in 0..5 {
+        h[index] = (h[index] & !mask) | (g[index] & mask);
+    }
+    h
+}
+
+/// Reads a little-endian word from the first 4 bytes of a slice.
+fn read_u32(bytes: &[u8]) -> u64 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::tests::decode_hex;
+
+    #[test]
+    fn tag_matches_the_rfc_8439_vector() {
+        let key: [u8; KEY_SIZE] =
+            decode_hex("85d6be7857556d337f4452fe42d506a8 0103808afb0db2fd4abff6af4149f51b")
+                .try_into()
+                .unwrap();
+        let tag: [u8; TAG_SIZE] = poly1305_tag(b"Cryptographic Forum Research Group", &key);
+        assert_eq!(tag.to_vec(), decode_hex("a8061dc1305136c6c22b8baf0c0127a9"));
+    }
+}
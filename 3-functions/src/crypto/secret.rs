@@ -0,0 +1,88 @@
+//! Handling of key material: zeroized when dropped and compared in constant time.
+
+use std::fmt;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/// A `struct` that holds a key or password and overwrites it with zeros when dropped.
+///
+/// It has no `Display` and its `Debug` hides the content, so it can not be printed or
+/// logged by mistake; the text is only reachable with [`SecretString::expose`].
+/// ### Example
+/// ```
+/// use functions::crypto::secret::SecretString;
+///
+/// let password: SecretString = SecretString::new("correct horse".to_string());
+/// assert_eq!(password.expose(), "correct horse");
+/// assert_eq!(format!("{:?}", password), "SecretString(***)");
+/// ```
+#[derive(Clone, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Takes ownership of a text, so no other copy is left behind.
+    pub fn new(text: String) -> SecretString {
+        SecretString(text)
+    }
+
+    /// Gets the secret text, to be used right away and never printed.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Checks if the secret text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("SecretString(***)")
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        let mut bytes: Vec<u8> = std::mem::take(&mut self.0).into_bytes();
+        zeroize(&mut bytes);
+    }
+}
+
+/// Overwrites some bytes with zeros, in a way the compiler can not optimize away.
+/// ### Arguments
+/// * `bytes` - The bytes of a key or any other secret, no longer needed.
+pub fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: the pointer comes from a mutable reference, so it is valid and aligned.
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Compares two byte slices in a time that does not depend on where they differ,
+/// so an attacker can not guess a tag byte by byte measuring the time.
+/// ### Arguments
+/// * `first_bytes` - The first slice, like the expected tag.
+/// * `second_bytes` - The second slice, like the received tag.
+/// ### Returns
+/// * `bool` - True if both slices have the same length and bytes.
+/// ### Example
+/// ```
+/// use functions::crypto::secret::constant_time_eq;
+///
+/// assert!(constant_time_eq(b"tag", b"tag"));
+/// assert!(!constant_time_eq(b"tag", b"tab"));
+/// assert!(!constant_time_eq(b"tag", b"tags"));
+/// ```
+pub fn constant_time_eq(first_bytes: &[u8], second_bytes: &[u8]) -> bool {
+    if first_bytes.len() != second_bytes.len() {
+        return false;
+    }
+    let difference: u8 = first_bytes
+        .iter()
+        .zip(second_bytes)
+        .fold(0, |difference, (first_byte, second_byte)| {
+            difference | (first_byte ^ second_byte)
+        });
+    std::hint::black_box(difference) == 0
+}
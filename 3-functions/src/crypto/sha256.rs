@@ -0,0 +1,246 @@
+//! The SHA-256 hash, HMAC and PBKDF2, used to derive keys from passwords.
+
+use super::secret::zeroize;
+
+/// The size in bytes of a SHA-256 digest.
+pub const DIGEST_SIZE: usize = 32;
+/// The size in bytes of the blocks hashed by SHA-256.
+const BLOCK_SIZE: usize = 64;
+/// The first 32 bits of the fractional parts of the cube roots of the first 64 primes.
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+/// The first 32 bits of the fractional parts of the square roots of the first 8 primes.
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// A `struct` that hashes a message given in parts with **SHA-256**.
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: Vec<u8>,
+    total_length: u64,
+}
+
+impl Sha256 {
+    /// Creates an empty hash.
+    pub fn new() -> Sha256 {
+        Sha256 {
+            state: INITIAL_STATE,
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            total_length: 0,
+        }
+    }
+
+    /// Adds some bytes to the hashed message.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.total_length += bytes.len() as u64;
+        self.buffer.extend_from_slice(bytes);
+        let full_length: usize = self.buffer.len() - self.buffer.len() % BLOCK_SIZE;
+        for block in self.buffer[..full_length].chunks(BLOCK_SIZE) {
+            compress(&mut self.state, block);
+        }
+        self.buffer.drain(..full_length);
+    }
+
+    /// Pads the message with its length and returns the digest.
+    pub fn finalize(mut self) -> [u8; DIGEST_SIZE] {
+        let bit_length: u64 = self.total_length * 8;
+        let mut padding: Vec<u8> = vec![0x80];
+        while (self.buffer.len() + padding.len()) % BLOCK_SIZE != BLOCK_SIZE - 8 {
+            padding.push(0);
+        }
+        padding.extend_from_slice(&bit_length.to_be_bytes());
+        self.update(&padding);
+        let mut digest: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+        for (index, word) in self.state.iter().enumerate() {
+            digest[index * 4..index * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    /// Overwrites the state and the whole buffer with zeros, so no hashed bytes are left.
+    fn zeroize(&mut self) {
+        for word in self.state.iter_mut() {
+            // SAFETY: the pointer comes from a mutable reference, so it is valid and aligned.
+            unsafe { std::ptr::write_volatile(word, 0) };
+        }
+        self.buffer.resize(self.buffer.capacity(), 0);
+        zeroize(&mut self.buffer);
+        self.total_length = 0;
+    }
+}
+
+/// The state of a keyed hash, like the ones of an HMAC and their copies, is as secret
+/// as the key, so every hash is zeroized when dropped, also after [`Sha256::finalize`].
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hashes a block of 64 bytes into the state.
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut schedule: [u32; 64] = [0; 64];
+    for (index, word) in block.chunks(4).enumerate() {
+        schedule[index] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for index in 16..64 {
+        let previous: u32 = schedule[index - 2];
+        let older: u32 = schedule[index - 15];
+        let sigma1: u32 = previous.rotate_right(17) ^ previous.rotate_right(19) ^ (previous >> 10);
+        let sigma0: u32 = older.rotate_right(7) ^ older.rotate_right(18) ^ (older >> 3);
+        schedule[index] = sigma1
+            .wrapping_add(schedule[index - 7])
+            .wrapping_add(sigma0)
+            .wrapping_add(schedule[index - 16]);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for index in 0..64 {
+        let sum1: u32 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let choice: u32 = (e & f) ^ (!e & g);
+        let temp1: u32 = h
+            .wrapping_add(sum1)
+            .wrapping_add(choice)
+            .wrapping_add(ROUND_CONSTANTS[index])
+            .wrapping_add(schedule[index]);
+        let sum0: u32 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let majority: u32 = (a & b) ^ (a & c) ^ (b & c);
+        let temp2: u32 = sum0.wrapping_add(majority);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// A `struct` that authenticates messages with **HMAC-SHA256**.
+///
+/// The inner and outer hashes are prepared once, so the same key can be reused cheaply.
+/// They and their copies in every [`HmacSha256::authenticate`] are zeroized when dropped.
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    /// Creates an HMAC with a key of any length.
+    pub fn new(key: &[u8]) -> HmacSha256 {
+        let mut block_key: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            let mut key_hash: Sha256 = Sha256::new();
+            key_hash.update(key);
+            block_key[..DIGEST_SIZE].copy_from_slice(&key_hash.finalize());
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+        let mut inner: Sha256 = Sha256::new();
+        inner.update(&block_key.map(|byte| byte ^ 0x36));
+        let mut outer: Sha256 = Sha256::new();
+        outer.update(&block_key.map(|byte| byte ^ 0x5c));
+        zeroize(&mut block_key);
+        HmacSha256 { inner, outer }
+    }
+
+    /// Computes the authentication code of a message.
+    pub fn authenticate(&self, message: &[u8]) -> [u8; DIGEST_SIZE] {
+        let mut inner: Sha256 = self.inner.clone();
+        inner.update(message);
+        let mut outer: Sha256 = self.outer.clone();
+        outer.update(&inner.finalize());
+        outer.finalize()
+    }
+}
+
+/// Derives a key from a password with **PBKDF2-HMAC-SHA256**.
+///
+/// Every iteration hashes the previous result again, so guessing passwords gets
+/// as slow as the number of iterations.
+/// ### Arguments
+/// * `password` - The bytes of the password.
+/// * `salt` - The random salt, different for every file, so equal passwords give different keys.
+/// * `iterations` - The cost of the derivation, at least 1.
+/// ### Returns
+/// * `[u8; 32]` - The derived key.
+pub fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; DIGEST_SIZE] {
+    let hmac: HmacSha256 = HmacSha256::new(password);
+    let mut first_message: Vec<u8> = salt.to_vec();
+    first_message.extend_from_slice(&1u32.to_be_bytes());
+    let mut block: [u8; DIGEST_SIZE] = hmac.authenticate(&first_message);
+    let mut key: [u8; DIGEST_SIZE] = block;
+    for _ in 1..iterations {
+        block = hmac.authenticate(&block);
+        for (key_byte, block_byte) in key.iter_mut().zip(block.iter()) {
+            *key_byte ^= block_byte;
+        }
+    }
+    zeroize(&mut block);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::tests::decode_hex;
+
+    #[test]
+    fn hash_and_hmac_match_the_reference_vectors() {
+        let mut hash: Sha256 = Sha256::new();
+        hash.update(b"abc");
+        assert_eq!(
+            hash.finalize().to_vec(),
+            decode_hex("ba7816bf8f01cfea414140de5dae2223 b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            HmacSha256::new(b"Jefe")
+                .authenticate(b"what do ya want for nothing?")
+                .to_vec(),
+            decode_hex("5bdcc146bf60754e6a042426089575c7 5a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn zeroize_wipes_the_state_and_the_spare_buffer() {
+        let mut hash: Sha256 = HmacSha256::new(b"secret key").inner.clone();
+        hash.update(b"a partial block");
+        hash.zeroize();
+        assert_eq!(hash.state, [0; 8]);
+        assert_eq!(hash.buffer.len(), hash.buffer.capacity());
+        assert!(hash.buffer.iter().all(|byte| *byte == 0));
+    }
+
+    /// The RFC 7914 vectors derive 64 bytes; the first 32 are the key this function returns.
+    #[test]
+    fn pbkdf2_matches_the_rfc_7914_vectors() {
+        assert_eq!(
+            pbkdf2_sha256(b"passwd", b"salt", 1).to_vec(),
+            decode_hex("55ac046e56e3089fec1691c22544b605 f94185216dde0465e68b9d57c20dacbc")
+        );
+        assert_eq!(
+            pbkdf2_sha256(b"Password", b"NaCl", 80_000).to_vec(),
+            decode_hex("4ddcd8f60b98be21830cee5ef22701f9 641a4418d04c0414aeff08876b34ab56")
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Reading and writing files or the standard streams, atomically and in chunks.
+
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::process;
+
+use crate::ciphers::CharStream;
+use crate::container::{self, ContainerSettings};
+use crate::stream;
+
+/// The name used for the standard input and output streams.
+pub const STANDARD_STREAM: &str = "-";
+
+/// Opens a file, or the standard input when the name is `-`, to read it later.
+/// ### Arguments
+/// - `file_name` - A string slice that holds the name of the file to open.
+/// ### Returns
+/// - `Result<Box<dyn Read>, std::io::Error>` - The opened input, or an error if not found.
+pub fn open_input(file_name: &str) -> Result<Box<dyn Read>, std::io::Error> {
+    if file_name == STANDARD_STREAM {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(fs::File::open(file_name)?))
+}
+
+/// Reads all the bytes of an opened input.
+///
+/// Unlike [`read_input_text`], the content can be any binary data, like images or archives.
+/// ### Arguments
+/// - `input` - The input to read.
+/// ### Returns
+/// - `Result<Vec<u8>, std::io::Error>` - The bytes of the input.
+pub fn read_input_bytes(input: &mut dyn Read) -> Result<Vec<u8>, std::io::Error> {
+    let mut content: Vec<u8> = vec![];
+    input.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Reads all the text of an opened input.
+/// ### Arguments
+/// - `input` - The input to read.
+/// ### Returns
+/// - `Result<String, std::io::Error>` - The text of the input, or an error if it is not UTF-8.
+pub fn read_input_text(input: &mut dyn Read) -> Result<String, std::io::Error> {
+    String::from_utf8(read_input_bytes(input)?)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+}
+
+/// Writes the content to a file, or to the standard output when the name is `-`.
+/// ### Arguments
+/// - `file_name` - A string slice that holds the name of the file to write.
+/// - `content` - A slice with the bytes to write, of a text or of any binary data.
+/// - `force` - A bool that allows overwriting an existing file.
+/// - `container` - Some `ContainerSettings` to wrap the content in a container, or None.
+/// ### Returns
+/// - `Result<(), std::io::Error>` - An error if the file exists and `force` is false,
+///   or if it could not be written.
+pub fn write_output(
+    file_name: &str,
+    content: &[u8],
+    force: bool,
+    container: Option<&ContainerSettings>,
+) -> Result<(), std::io::Error> {
+    write_atomically(file_name, force, |writer| match container {
+        Some(settings) => {
+            container::write_container(writer, settings, |writer| writer.write_all(content))
+        }
+        None => writer.write_all(content),
+    })
+}
+
+/// Opens a file, or the standard output when the name is `-`, and writes to it with a function.
+///
+/// The file is written atomically: the content goes to a temporary file
+/// in the same folder, that is then renamed to the final name.
+/// Without `force` it is hard linked instead, which fails if a file with that name
+/// was created in the meantime, so an existing file is never replaced.
+/// If the function fails, the temporary file is removed and the final one is untouched.
+/// ### Arguments
+/// - `file_name` - A string slice that holds the name of the file to write.
+/// - `force` - A bool that allows overwriting an existing file.
+/// - `write` - A function that writes the content to a buffered writer.
+/// ### Returns
+/// - `Result<T, std::io::Error>` - The result of the function, or an error if the file exists
+///   and `force` is false, or if it could not be written.
+pub fn write_atomically<T>(
+    file_name: &str,
+    force: bool,
+    write: impl FnOnce(&mut dyn Write) -> Result<T, std::io::Error>,
+) -> Result<T, std::io::Error> {
+    if file_name == STANDARD_STREAM {
+        let mut stdout = BufWriter::new(io::stdout().lock());
+        let result: T = write(&mut stdout)?;
+        stdout.flush()?;
+        return Ok(result);
+    }
+    let path: &Path = Path::new(file_name);
+    if path.exists() && !force {
+        return Err(already_exists());
+    }
+    let temp_file_name: String = format!("{}.{}.tmp", file_name, process::id());
+    let write_temp_file = || -> Result<T, std::io::Error> {
+        let mut temp_file = BufWriter::new(fs::File::create(&temp_file_name)?);
+        let result: T = write(&mut temp_file)?;
+        temp_file.flush()?;
+        drop(temp_file);
+        if force {
+            fs::rename(&temp_file_name, path)?;
+        } else {
+            fs::hard_link(&temp_file_name, path).map_err(|error| {
+                if error.kind() == std::io::ErrorKind::AlreadyExists {
+                    already_exists()
+                } else {
+                    error
+                }
+            })?;
+            fs::remove_file(&temp_file_name)?;
+        }
+        Ok(result)
+    };
+    write_temp_file().inspect_err(|_| {
+        let _ = fs::remove_file(&temp_file_name);
+    })
+}
+
+/// Encrypts or decrypts the input to the output in chunks, without loading it whole.
+/// ### Arguments
+/// * `output_file_name` - A string slice with the name of the file to write.
+/// * `force` - A bool that allows overwriting an existing file.
+/// * `input` - The input to read.
+/// * `char_stream` - A `CharStream` of the cipher, that keeps the key position between chunks.
+/// * `container` - Some `ContainerSettings` to wrap the output in a container, or None.
+/// ### Returns
+/// * `Result<u64, std::io::Error>` - The number of bytes processed, or the first error.
+pub fn stream_input(
+    output_file_name: &str,
+    force: bool,
+    input: &mut dyn Read,
+    char_stream: &mut CharStream,
+    container: Option<&ContainerSettings>,
+) -> Result<u64, std::io::Error> {
+    write_atomically(output_file_name, force, |writer| match container {
+        Some(settings) => container::write_container(writer, settings, |writer| {
+            stream::stream_text(input, writer, char_stream, stream::CHUNK_SIZE)
+        }),
+        None => stream::stream_text(input, writer, char_stream, stream::CHUNK_SIZE),
+    })
+}
+
+/// Creates the error for an output file that exists and can not be overwritten.
+fn already_exists() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        "⚠️ - The output file already exists, use --force to overwrite it.",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn existing_files_are_only_replaced_with_force() {
+        let path = std::env::temp_dir().join(format!("rs-lab-output-{}.txt", process::id()));
+        let file_name: &str = path.to_str().unwrap();
+        let _ = fs::remove_file(&path);
+        write_output(file_name, b"first", false, None).unwrap();
+        let error: std::io::Error = write_output(file_name, b"second", false, None).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+        write_output(file_name, b"third", true, None).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"third");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_file_created_while_writing_is_not_replaced() {
+        let path = std::env::temp_dir().join(format!("rs-lab-race-{}.txt", process::id()));
+        let file_name: &str = path.to_str().unwrap();
+        let _ = fs::remove_file(&path);
+        let error: std::io::Error = write_atomically(file_name, false, |writer| {
+            fs::write(&path, b"created meanwhile")?;
+            writer.write_all(b"ours")
+        })
+        .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&path).unwrap(), b"created meanwhile");
+        assert!(!Path::new(&format!("{}.{}.tmp", file_name, process::id())).exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+//! Classical and modern ciphers to encrypt and decrypt texts and files.
+//!
+//! The ciphers are created by name with [`ciphers::new_cipher`] for texts
+//! and [`ciphers::new_byte_cipher`] for any bytes, and every fallible function
+//! returns a `Result` instead of ending the program, so other tools can reuse them.
+//! ### Example
+//! ```
+//! use functions::ciphers::{new_cipher, Alphabet, Mode};
+//!
+//! let cipher = new_cipher("vigenere", "lemon", &Alphabet::english()).unwrap();
+//! let encrypted: String = cipher.apply("attack at dawn", Mode::Encrypt);
+//! assert_eq!(encrypted, "lxfopv ef rnhr");
+//! assert_eq!(cipher.apply(&encrypted, Mode::Decrypt), "attack at dawn");
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod analysis;
+pub mod batch;
+pub mod ciphers;
+pub mod container;
+pub mod cryptanalysis;
+pub mod crypto;
+pub mod files;
+pub mod pipeline;
+pub mod stream;
+pub mod telegram;
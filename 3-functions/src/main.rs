@@ -1,160 +1,110 @@
-// Use the Caesar cipher to encrypt and decrypt files using a key from the command line.
-
-use std::env;
-use std::fs;
+//! Use classical ciphers to encrypt and decrypt files using a key from the command line.
+//!
+//! The work is done by the `functions` library; this binary only parses the arguments,
+//! prints the progress and turns the errors into exit codes.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::time::Instant;
 
-const CASE_LENGTH: u8 = 26;
-const UPPER_CASE_BASE: u8 = 'A' as u8;
-const LOWER_CASE_BASE: u8 = 'a' as u8;
-
-/// A `struct` that holds the command line arguments.
-/// It is used to store the _clean file name_ to read and the _key string_ used as the secret.
-struct CliArgs {
-    clean_file_name: String,
-    key_string: String,
-}
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
 
-/// Prints the instructions for the program.
-fn print_instructions() -> () {
-    println!("🔑 Encrypt and decrypt files using the Caesar cipher.");
-    println!("📘  The program reads the file content and encrypts it using the key.");
-    println!("📘  The key is a string that is used to shift the characters in the text.");
-    println!("📘  The program prints the original and encrypted text to the console.");
-    println!("📘  The program requires two command line arguments: the file name and the key.");
-    println!("🚀 Example: cargo run example.txt key");
-}
+use cli::CliArgs;
+use functions::files::{open_input, read_input_text, write_output};
+use functions::pipeline::{self, FileSettings};
+use functions::{analysis, batch};
 
-/// Reads the command line arguments.
-///
-/// The function reads the command line arguments and returns a `CliArgs` struct.
-/// ### Returns
-/// - A `Result` with the `CliArgs` struct if the args could be read.
-/// - Otherwise, If the number of arguments is not 3 it returns an error message.
-fn read_args() -> Result<CliArgs, std::io::Error> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        print_instructions();
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            "⚠️ - Please provide the file name and key as arguments.",
-        ));
-    }
-    let cli_args = CliArgs {
-        clean_file_name: args[1].clone(),
-        key_string: args[2].clone(),
-    };
-    return Ok(cli_args);
-}
+mod cli;
+mod repl;
 
-/// Reads the text content of a file.
-///
-/// If the file is not found, or if the file is not valid UTF-8,
-/// then returns an error message.
+/// Gets the report of the analysis, crack or crib modes, reading the input.
 /// ### Arguments
-/// - `file_name` - A string slice that holds the name of the file to read.
+/// * `cli_args` - A `CliArgs` struct with the mode, the cipher and the input.
 /// ### Returns
-/// - `Result<String, std::io::Error>` - The content of the file.
-/// ### Example
-/// ```
-/// let content = read_file("example.txt");
-/// ```
-fn read_file(file_name: &String) -> String {
-    let content: Result<String, std::io::Error> = fs::read_to_string(file_name);
-    match content {
-        Ok(content) => return content,
-        Err(error) => {
-            eprintln!("💣 Error reading file: {}", error);
-            std::process::exit(1)
-        }
+/// * `Result<String, std::io::Error>` - The report or decrypted text, or the first error.
+fn get_report(cli_args: &CliArgs) -> Result<String, std::io::Error> {
+    if let Some(crib) = &cli_args.crib {
+        return pipeline::crib_input(
+            &cli_args.cipher_name,
+            &cli_args.clean_file_name,
+            cli_args.crib_file_name.as_deref(),
+            crib,
+        );
     }
-}
-
-/// Encrypts a string using the **Caesar cipher**.
-///
-/// This function takes a clean string and a key password as input.
-/// It applies the Caesar cipher to each character in the string,
-/// using the key password as a source of shift values.
-/// The shift values are rotated for each character.
-/// > Note: This function is implemented using loops instead of iterators.
-/// ### Arguments
-/// * `input` - A string slice that holds the clean text to be encrypted.
-/// - `key` - A string slice that holds the key password.
-/// ### Returns
-/// - `String` - The encrypted text.
-/// ### Example
-/// ```
-/// let encrypted = caesar_cipher_with_password("hello", "key");
-/// ```
-fn caesar_cipher_text(clean_string: &str, key_string: &str) -> String {
-    let mut key_index: usize = 0;
-    let mut ciphered_string: String = String::new();
-    for clean_char in clean_string.chars() {
-        let key_char: char = key_string.chars().nth(key_index).unwrap_or_default();
-        let shift: u8 = key_char as u8;
-        let ciphered_char: char = caesar_cipher_char(clean_char, shift);
-        ciphered_string.push(ciphered_char);
-        key_index = get_next_key_index(key_index, key_string);
+    let input_text: String = read_input_text(&mut open_input(&cli_args.clean_file_name)?)?;
+    if cli_args.crack {
+        return pipeline::crack_text(
+            &cli_args.cipher_name,
+            &cli_args.alphabet,
+            &input_text,
+            &|message| print_progress(cli_args, message),
+        );
+    }
+    let report: analysis::FrequencyReport = analysis::analyze_text(&input_text);
+    if cli_args.report_format == "json" {
+        Ok(analysis::format_json(&report))
+    } else {
+        Ok(analysis::format_text(&report))
     }
-    return ciphered_string;
-}
-
-/// Gets the next rotating index from a key string.
-///
-/// This function is used to rotate the key string for each character.
-/// If the current index is at the end of the key string, then it wraps around to the start.
-/// Otherwise, it increments the current index by 1.
-/// ### Arguments
-/// * `current_index` - A usize that holds the current index.
-/// * `key_string` - A string slice that holds the key password.
-/// ### Returns
-/// * `usize` - The next rotating index.
-fn get_next_key_index(current_index: usize, key_string: &str) -> usize {
-    let next_index: usize = current_index + 1;
-    let rotated_index: usize = next_index % key_string.len();
-    return rotated_index;
 }
 
-/// Encrypts a character using the **Caesar cipher**.
-///
-/// This function takes a clean character and a shift value as input.
-/// It applies the Caesar cipher to the character using the shift value.
-/// If the character is not an ASCII alphabetic character, then it is left unchanged.
+/// Encrypts or decrypts every file of the input directory into the output directory,
+/// in parallel with a live progress line,
+/// and prints a summary with the result and timing of every file.
 /// ### Arguments
-/// * `clean_char` - A character that holds the clean text to be encrypted.
-/// * `shift` - A u8 that holds the shift value.
+/// * `cli_args` - A `CliArgs` struct with the directories, the patterns and the cipher options.
 /// ### Returns
-/// * `char` - The encrypted character.
-/// ### Example
-/// ```
-/// let encrypted = caesar_cipher_char('a', 3);
-/// ```
-fn caesar_cipher_char(clean_char: char, shift: u8) -> char {
-    let base_case_code: u8 = match get_base_code_option(clean_char) {
-        None => return clean_char,
-        Some(base_case_code) => base_case_code,
+/// * `bool` - True if every file was processed.
+fn process_directory(cli_args: &CliArgs) -> bool {
+    let input_root: &Path = Path::new(&cli_args.clean_file_name);
+    let output_root: &Path = Path::new(&cli_args.output_file_name);
+    let relative_paths: Vec<PathBuf> = match batch::find_files(
+        input_root,
+        &cli_args.include_patterns,
+        &cli_args.exclude_patterns,
+        output_root,
+    ) {
+        Ok(relative_paths) => relative_paths,
+        Err(error) => {
+            eprintln!("💣 Error reading the directory: {}", error);
+            process::exit(EXIT_FAILURE);
+        }
     };
-    let clean_code: u8 = clean_char as u8;
-    let ciphered_code: u8 = ((clean_code - base_case_code + shift) % CASE_LENGTH) + base_case_code;
-    let ciphered_char: char = ciphered_code as char;
-    return ciphered_char;
-}
-
-/// Gets the base code for a character if it is an ASCII alphabetic character.
-/// ### Arguments
-/// * `the_char` - A `char` that holds the character to check.
-/// ### Returns
-/// * `Option<u8>` - Some base code for the character or None
-fn get_base_code_option(the_char: char) -> Option<u8> {
-    if the_char.is_ascii_alphabetic() == false {
-        return None;
+    print_progress(
+        cli_args,
+        &format!(
+            "🗂️ Found {} files to process with {} jobs",
+            relative_paths.len(),
+            cli_args.jobs
+        ),
+    );
+    let file_settings: FileSettings = cli_args.file_settings();
+    let results: Vec<batch::FileResult> = batch::process_files(
+        input_root,
+        output_root,
+        &relative_paths,
+        cli_args.jobs,
+        &|progress_line| {
+            if !cli_args.quiet {
+                eprint!("\r{:<60}", progress_line);
+            }
+        },
+        |input_path, output_path| {
+            pipeline::process_file(
+                &file_settings,
+                &input_path.to_string_lossy(),
+                &output_path.to_string_lossy(),
+                &|_| {},
+            )
+        },
+    );
+    if !cli_args.quiet && !results.is_empty() {
+        eprintln!();
     }
-    let base_case_code: u8 = if the_char.is_ascii_lowercase() {
-        LOWER_CASE_BASE
-    } else {
-        UPPER_CASE_BASE
-    };
-    Some(base_case_code)
+    print!("{}", batch::format_summary(&results));
+    results.iter().all(|result| result.outcome.is_ok())
 }
 
 /// Prints the en of the program and the duration based on the start time.
@@ -163,7 +113,7 @@ fn get_base_code_option(the_char: char) -> Option<u8> {
 fn print_end(start_time: std::time::Instant) {
     let duration = start_time.elapsed();
     let duration_ms = get_milliseconds(duration);
-    println!("🦀 Program completed in: {:?} ms", duration_ms);
+    eprintln!("🦀 Program completed in: {:?} ms", duration_ms);
 }
 
 /// Gets milliseconds from a duration
@@ -175,32 +125,119 @@ fn get_milliseconds(duration: std::time::Duration) -> u64 {
     duration.as_secs() * 1000 + duration.subsec_millis() as u64
 }
 
-/// The main function reads the command line arguments, reads the file content, and encrypts the text.
+/// Gets the exit code of an error: a usage error for an invalid input, like a wrong key,
+/// or a failure otherwise.
+/// ### Arguments
+/// * `error` - The error that ended the program.
+/// ### Returns
+/// * `i32` - `EXIT_USAGE` or `EXIT_FAILURE`.
+fn get_exit_code(error: &std::io::Error) -> i32 {
+    if error.kind() == io::ErrorKind::InvalidInput {
+        EXIT_USAGE
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+/// Prints a progress message to the standard error unless the program runs quietly.
+/// ### Arguments
+/// * `cli_args` - A `CliArgs` struct with the `quiet` option.
+/// * `message` - A string slice with the message to print.
+fn print_progress(cli_args: &CliArgs, message: &str) {
+    if !cli_args.quiet {
+        eprintln!("{}", message);
+    }
+}
+
+/// The main function reads the command line arguments, reads the file content, and encrypts or decrypts the text.
 ///  
-/// It then prints the original and encrypted text to the console.
+/// It then writes the processed text to the console or to the output file.
+/// The ciphers that work char by char stream the content in chunks, so big files fit in memory.
+/// A directory input is processed file by file into an output directory with the same tree.
+/// The progress messages are printed to the standard error, so they never mix with the output.
+/// Every error ends the program with a non-zero exit code.
 /// ### Example
 /// ```
-/// cargo run example.txt key
+/// cargo run -- --input example.txt --key key --output encrypted.txt
 /// ```
 fn main() {
     let start = Instant::now();
-    let cli_args_result: Result<CliArgs, std::io::Error> = read_args();
+    let cli_args_result: Result<CliArgs, std::io::Error> = cli::read_args();
 
     let cli_args: CliArgs = match cli_args_result {
         Ok(cli_args) => cli_args,
         Err(error) => {
             eprintln!("💣 Error reading command line arguments: {}", error);
-            std::process::exit(0);
+            eprintln!("📘 Use --help to see the available flags.");
+            process::exit(EXIT_USAGE);
         }
     };
+    if cli_args.help {
+        cli::print_instructions();
+        return;
+    }
+    if cli_args.interactive {
+        if let Err(error) = repl::run(&cli_args) {
+            eprintln!("💣 Error in the interactive mode: {}", error);
+            process::exit(EXIT_FAILURE);
+        }
+        return;
+    }
 
-    println!("📖 Reading content from: {}", &cli_args.clean_file_name);
-    let clean_text: String = read_file(&cli_args.clean_file_name);
-    println!("⛲ Original text:\n{}", &clean_text);
+    print_progress(
+        &cli_args,
+        &format!("📖 Reading content from: {}", &cli_args.clean_file_name),
+    );
+    if cli_args.analyze || cli_args.crack || cli_args.crib.is_some() {
+        let processed_text: String = match get_report(&cli_args) {
+            Ok(processed_text) => processed_text,
+            Err(error) => {
+                eprintln!("💣 Error processing the content: {}", error);
+                process::exit(EXIT_FAILURE);
+            }
+        };
+        print_progress(
+            &cli_args,
+            &format!("💾 Writing content to: {}", &cli_args.output_file_name),
+        );
+        if let Err(error) = write_output(
+            &cli_args.output_file_name,
+            processed_text.as_bytes(),
+            cli_args.force,
+            None,
+        ) {
+            eprintln!("💣 Error writing output: {}", error);
+            process::exit(EXIT_FAILURE);
+        }
+    } else if Path::new(&cli_args.clean_file_name).is_dir() {
+        if !process_directory(&cli_args) {
+            process::exit(EXIT_FAILURE);
+        }
+    } else if let Err(error) = pipeline::process_file(
+        &cli_args.file_settings(),
+        &cli_args.clean_file_name,
+        &cli_args.output_file_name,
+        &|message| print_progress(&cli_args, message),
+    ) {
+        eprintln!("💣 Error processing the content: {}", error);
+        process::exit(get_exit_code(&error));
+    }
 
-    println!("🕵️‍♀️ Cipher with key :\n{}", &cli_args.key_string);
-    let encrypted = caesar_cipher_text(&clean_text, &cli_args.key_string);
-    println!("🕵️‍♀️ Encrypted text:\n{}", encrypted);
+    if !cli_args.quiet {
+        print_end(start);
+    }
+}
 
-    print_end(start);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_arguments_exit_with_the_usage_code() {
+        let args: Vec<String> = vec!["--cipher".to_string(), "enigma".to_string()];
+        let error: std::io::Error = cli::parse_args(&args).err().unwrap();
+        assert_eq!(get_exit_code(&error), EXIT_USAGE);
+        let error: std::io::Error = std::io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(get_exit_code(&error), EXIT_FAILURE);
+    }
 }
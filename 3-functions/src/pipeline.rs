@@ -0,0 +1,298 @@
+//! The whole work of the program on one input: encrypting or decrypting a file,
+//! cracking its key or attacking it with a crib.
+//!
+//! The progress of every step is reported to a function, so the caller decides
+//! where to print it, if anywhere.
+
+use std::io::Read;
+
+use crate::ciphers::{self, Alphabet, ByteCipher, Cipher, Mode};
+use crate::container::{self, ContainerSettings, DetectedInput};
+use crate::cryptanalysis;
+use crate::crypto::secret::SecretString;
+use crate::files::{self, open_input, read_input_bytes, read_input_text, write_output};
+use crate::telegram::{self, TextFormat};
+
+/// The most readable fragments reported by the xor crib dragging.
+pub const MAX_CRIB_MATCHES: usize = 10;
+
+/// A `struct` with the settings to encrypt or decrypt a file.
+#[derive(Clone)]
+pub struct FileSettings {
+    /// The name of the cipher, replaced by the one of a container when decrypting.
+    pub cipher_name: String,
+    /// The key, or the password of the binary ciphers that derive a key.
+    pub key_string: SecretString,
+    /// The PBKDF2 iterations to derive a key from the password when encrypting.
+    pub kdf_iterations: u32,
+    /// The alphabet of the text ciphers, replaced by the one of a container when decrypting.
+    pub alphabet: Alphabet,
+    /// Whether to encrypt or decrypt.
+    pub mode: Mode,
+    /// Whether to overwrite an existing output file.
+    pub force: bool,
+    /// Whether to wrap the encrypted content in a container with the settings.
+    pub container: bool,
+    /// The telegram options of the text.
+    pub text_format: TextFormat,
+}
+
+/// Encrypts or decrypts a file with the cipher, key and options of the settings.
+///
+/// When decrypting a container, its cipher and alphabet replace the ones of the settings.
+/// The ciphers that work char by char stream the content, so big files fit in memory.
+/// ### Arguments
+/// * `settings` - The `FileSettings` with the cipher, the key and the options.
+/// * `input_file_name` - A string slice with the file to read, or `-` for the standard input.
+/// * `output_file_name` - A string slice with the file to write, or `-` for the standard output.
+/// * `report_progress` - A function called with a message for every step.
+/// ### Returns
+/// * `Result<u64, std::io::Error>` - The number of bytes read, or the first error.
+///   The errors of the key are of the `InvalidInput` kind.
+/// ### Example
+/// ```
+/// use functions::ciphers::{Alphabet, Mode};
+/// use functions::crypto::secret::SecretString;
+/// use functions::pipeline::{process_file, FileSettings};
+/// use functions::telegram::TextFormat;
+///
+/// let input_path = std::env::temp_dir().join("pipeline-example.txt");
+/// let output_path = std::env::temp_dir().join("pipeline-example.enc");
+/// std::fs::write(&input_path, "attack at dawn").unwrap();
+/// let settings = FileSettings {
+///     cipher_name: "vigenere".to_string(),
+///     key_string: SecretString::new("lemon".to_string()),
+///     kdf_iterations: 1,
+///     alphabet: Alphabet::english(),
+///     mode: Mode::Encrypt,
+///     force: true,
+///     container: false,
+///     text_format: TextFormat::default(),
+/// };
+/// let input_name: &str = input_path.to_str().unwrap();
+/// let output_name: &str = output_path.to_str().unwrap();
+/// assert_eq!(process_file(&settings, input_name, output_name, &|_| {}).unwrap(), 14);
+/// assert_eq!(std::fs::read_to_string(&output_path).unwrap(), "lxfopv ef rnhr");
+/// ```
+pub fn process_file(
+    settings: &FileSettings,
+    input_file_name: &str,
+    output_file_name: &str,
+    report_progress: &dyn Fn(&str),
+) -> Result<u64, std::io::Error> {
+    let mut input: Box<dyn Read> = open_input(input_file_name)?;
+    let mut cipher_name: String = settings.cipher_name.clone();
+    let mut alphabet: Alphabet = settings.alphabet.clone();
+    if settings.mode == Mode::Decrypt {
+        input = match container::detect_container(input)? {
+            DetectedInput::Plain(input) => input,
+            DetectedInput::Container(container_settings, content) => {
+                report_progress(&format!(
+                    "📦 Container with cipher: {}",
+                    &container_settings.cipher_name
+                ));
+                cipher_name = container_settings.cipher_name;
+                if let Some(container_alphabet) = container_settings.alphabet {
+                    alphabet = container_alphabet;
+                }
+                content
+            }
+        };
+    }
+    let binary: bool = ciphers::get_cipher_info(&cipher_name).is_some_and(|info| info.binary);
+    let container_settings: Option<ContainerSettings> =
+        (settings.container && settings.mode == Mode::Encrypt).then(|| ContainerSettings {
+            cipher_name: cipher_name.clone(),
+            alphabet: (!binary).then(|| alphabet.clone()),
+        });
+    if binary {
+        let cipher: Box<dyn ByteCipher> = ciphers::new_byte_cipher(
+            &cipher_name,
+            settings.key_string.expose(),
+            settings.kdf_iterations,
+        )?;
+        let clean_bytes: Vec<u8> = read_input_bytes(&mut input)?;
+        let processed_bytes: Vec<u8> = cipher.apply_bytes(&clean_bytes, settings.mode)?;
+        report_progress(&format!("💾 Writing content to: {}", output_file_name));
+        write_output(
+            output_file_name,
+            &processed_bytes,
+            settings.force,
+            container_settings.as_ref(),
+        )?;
+        return Ok(clean_bytes.len() as u64);
+    }
+    let cipher: Box<dyn Cipher> =
+        ciphers::new_cipher(&cipher_name, settings.key_string.expose(), &alphabet)?;
+    let text_format: &TextFormat = &settings.text_format;
+    if let Some(mut char_stream) = cipher
+        .char_stream(settings.mode)
+        .filter(|_| text_format.is_plain())
+    {
+        report_progress(&format!("🌊 Streaming content to: {}", output_file_name));
+        let total_bytes: u64 = files::stream_input(
+            output_file_name,
+            settings.force,
+            &mut input,
+            &mut char_stream,
+            container_settings.as_ref(),
+        )?;
+        report_progress(&format!("🌊 Streamed {} bytes", total_bytes));
+        return Ok(total_bytes);
+    }
+    let clean_text: String = read_input_text(&mut input)?;
+    let processed_text: String = match settings.mode {
+        Mode::Encrypt => {
+            let ciphered_text: String = cipher.encrypt(&telegram::format_clean_text(
+                &clean_text,
+                text_format,
+                &alphabet,
+            ));
+            if text_format.group_size > 0 {
+                telegram::group_ciphered_text(&ciphered_text, text_format.group_size)
+            } else {
+                ciphered_text
+            }
+        }
+        Mode::Decrypt if text_format.restore => cipher.decrypt(&telegram::join_groups(&clean_text)),
+        Mode::Decrypt => cipher.decrypt(&clean_text),
+    };
+    report_progress(&format!("💾 Writing content to: {}", output_file_name));
+    write_output(
+        output_file_name,
+        processed_text.as_bytes(),
+        settings.force,
+        container_settings.as_ref(),
+    )?;
+    Ok(clean_text.len() as u64)
+}
+
+/// Recovers the most likely key of a Caesar or Vigenère ciphered text and decrypts it.
+///
+/// The steps of the analysis are reported as progress messages.
+/// ### Arguments
+/// * `cipher_name` - A string slice with the cipher to crack, `caesar` or `vigenere`.
+/// * `alphabet` - The `Alphabet` of the text.
+/// * `ciphered_text` - A string slice with the text to crack.
+/// * `report_progress` - A function called with a message for every step.
+/// ### Returns
+/// * `Result<String, std::io::Error>` - The text decrypted with the most likely key,
+///   or an error if the cipher can not use the key found.
+/// ### Example
+/// ```
+/// use functions::ciphers::{new_cipher, Alphabet, Mode};
+/// use functions::cryptanalysis::get_caesar_key_char;
+/// use functions::pipeline::crack_text;
+///
+/// let clean_text: &str = "the quick brown fox jumps over the lazy dog near the river bank";
+/// let key_string: String = get_caesar_key_char(3).to_string();
+/// let cipher = new_cipher("caesar", &key_string, &Alphabet::english()).unwrap();
+/// let ciphered_text: String = cipher.apply(clean_text, Mode::Encrypt);
+/// let cracked_text: String =
+///     crack_text("caesar", &Alphabet::english(), &ciphered_text, &|_| {}).unwrap();
+/// assert_eq!(cracked_text, clean_text);
+/// ```
+pub fn crack_text(
+    cipher_name: &str,
+    alphabet: &Alphabet,
+    ciphered_text: &str,
+    report_progress: &dyn Fn(&str),
+) -> Result<String, std::io::Error> {
+    let key_string: String = if cipher_name == "vigenere" {
+        let analysis = cryptanalysis::crack_vigenere(ciphered_text);
+        for (factor, votes) in analysis.kasiski_votes.iter().take(5) {
+            report_progress(&format!("🔎 Kasiski factor {:>2}: {} votes", factor, votes));
+        }
+        for (key_length, coincidence) in analysis.coincidences.iter() {
+            report_progress(&format!(
+                "🔎 Key length {:>2}: index of coincidence {:.4}",
+                key_length, coincidence
+            ));
+        }
+        report_progress(&format!("🔎 Estimated key length: {}", analysis.key_length));
+        analysis.key_string
+    } else {
+        let analysis = cryptanalysis::crack_caesar(ciphered_text);
+        for (shift, distance) in analysis.ranking.iter().take(5) {
+            report_progress(&format!(
+                "🔎 Shift {:>2}: chi-squared {:.2}",
+                shift, distance
+            ));
+        }
+        let best_shift: u8 = analysis.ranking[0].0;
+        cryptanalysis::get_caesar_key_char(best_shift).to_string()
+    };
+    report_progress(&format!("🗝️ Most likely key: {}", key_string));
+    let cipher: Box<dyn Cipher> = ciphers::new_cipher(cipher_name, &key_string, alphabet)?;
+    Ok(cipher.decrypt(ciphered_text))
+}
+
+/// Attacks an input with a known part of its clean text, a crib, and reports the keys found.
+///
+/// For the caesar and vigenere ciphers, the crib is tried at every offset and
+/// only the offsets where the key repeats are reported.
+/// For the xor cipher, the crib is dragged over the input and a second file ciphered
+/// with the same key, reporting the most readable fragments of the second file.
+/// ### Arguments
+/// * `cipher_name` - A string slice with the cipher of the input.
+/// * `input_file_name` - A string slice with the file to attack, or `-` for the standard input.
+/// * `crib_file_name` - Some second file ciphered with the same xor key, or None.
+/// * `crib` - A string slice with the known text.
+/// ### Returns
+/// * `Result<String, std::io::Error>` - The report with the keys or fragments found,
+///   or an error if a file can not be read.
+pub fn crib_input(
+    cipher_name: &str,
+    input_file_name: &str,
+    crib_file_name: Option<&str>,
+    crib: &str,
+) -> Result<String, std::io::Error> {
+    let mut report: String = String::new();
+    if let Some(crib_file_name) = crib_file_name {
+        let first_bytes: Vec<u8> = read_input_bytes(&mut open_input(input_file_name)?)?;
+        let second_bytes: Vec<u8> = read_input_bytes(&mut open_input(crib_file_name)?)?;
+        let matches: Vec<cryptanalysis::DragMatch> =
+            cryptanalysis::drag_crib(&first_bytes, &second_bytes, crib.as_bytes());
+        for drag_match in matches.iter().take(MAX_CRIB_MATCHES) {
+            let key_hex: Vec<String> = drag_match
+                .key_fragment
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            report.push_str(&format!(
+                "🗝️ Offset {:>5}: other text {:?}, key bytes {}\n",
+                drag_match.offset,
+                String::from_utf8_lossy(&drag_match.other_fragment),
+                key_hex.join(" ")
+            ));
+        }
+        report.push_str(&format!(
+            "🔎 {} readable offsets, showing the best {}\n",
+            matches.len(),
+            matches.len().min(MAX_CRIB_MATCHES)
+        ));
+        return Ok(report);
+    }
+    let ciphered_text: String = read_input_text(&mut open_input(input_file_name)?)?;
+    let matches: Vec<cryptanalysis::CribMatch> = if cipher_name == "vigenere" {
+        cryptanalysis::crib_vigenere(&ciphered_text, crib)
+    } else {
+        cryptanalysis::crib_caesar(&ciphered_text, crib)
+    };
+    let mut consistent_count: usize = 0;
+    for crib_match in matches.iter() {
+        if let Some(key_string) = &crib_match.key_string {
+            consistent_count += 1;
+            report.push_str(&format!(
+                "🗝️ Offset {:>5}: key {} (fragment {})\n",
+                crib_match.offset, key_string, crib_match.key_fragment
+            ));
+        }
+    }
+    report.push_str(&format!(
+        "🔎 {} offsets tried, {} with a repeating key\n",
+        matches.len(),
+        consistent_count
+    ));
+    Ok(report)
+}
@@ -0,0 +1,250 @@
+//! An interactive prompt to encrypt and decrypt lines live, changing the settings with commands.
+
+use std::io::{self, BufRead, Write};
+
+use functions::ciphers::{self, new_alphabet, Alphabet, Cipher, Mode, ALPHABETS};
+use functions::cryptanalysis;
+use functions::crypto::secret::{zeroize, SecretString};
+
+use crate::cli::CliArgs;
+
+/// The commands of the prompt, with their argument and description, to print the help.
+const COMMANDS: [(&str, &str); 10] = [
+    (":key <key>", "Changes the key."),
+    (":cipher <name>", "Changes the cipher, keeping the key."),
+    (
+        ":alphabet <alphabet>",
+        "Changes the alphabet, by name or letters.",
+    ),
+    (":encrypt", "Encrypts the next lines."),
+    (":decrypt", "Decrypts the next lines."),
+    (
+        ":crack [text]",
+        "Finds the caesar or vigenere key of a text, or of the last ciphered line.",
+    ),
+    (":history", "Lists the lines processed and their results."),
+    (":settings", "Shows the cipher, alphabet and mode."),
+    (":help", "Prints this help."),
+    (":quit", "Ends the session, like an empty input."),
+];
+
+/// A `struct` with the settings of the prompt, that commands change between lines.
+///
+/// The history keeps every processed line with its result and the mode used, in order.
+struct Session {
+    cipher_name: String,
+    key_string: SecretString,
+    alphabet: Alphabet,
+    mode: Mode,
+    cipher: Option<Box<dyn Cipher>>,
+    history: Vec<(String, String, Mode)>,
+}
+
+impl Session {
+    /// Creates the cipher again after a change of the settings.
+    ///
+    /// When the key is missing or not valid, the cipher is removed until a new one is given.
+    fn update_cipher(&mut self) -> Result<(), std::io::Error> {
+        self.cipher = None;
+        let needs_key: bool =
+            ciphers::get_cipher_info(&self.cipher_name).is_some_and(|info| info.needs_key);
+        if needs_key && self.key_string.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "⚠️ - Please set a key with :key <key>.",
+            ));
+        }
+        self.cipher = Some(ciphers::new_cipher(
+            &self.cipher_name,
+            self.key_string.expose(),
+            &self.alphabet,
+        )?);
+        Ok(())
+    }
+
+    /// Gets a line describing the settings, without showing the key.
+    fn describe(&self) -> String {
+        let mode_name: &str = match self.mode {
+            Mode::Encrypt => "encrypt",
+            Mode::Decrypt => "decrypt",
+        };
+        format!(
+            "⚙️ Cipher {}, alphabet {}, mode {}, key of {} chars",
+            self.cipher_name,
+            self.alphabet.letters(),
+            mode_name,
+            self.key_string.expose().chars().count()
+        )
+    }
+}
+
+/// Runs the interactive prompt until the input ends or the user quits.
+///
+/// Every line is encrypted or decrypted with the current settings and printed right away.
+/// Lines starting with `:` are commands; their errors are printed and the session goes on.
+/// ### Arguments
+/// * `cli_args` - A `CliArgs` struct with the initial cipher, key, alphabet and mode.
+/// ### Returns
+/// * `Result<(), std::io::Error>` - An error only if the terminal can not be read or written.
+pub fn run(cli_args: &CliArgs) -> Result<(), std::io::Error> {
+    let mut session: Session = Session {
+        cipher_name: cli_args.cipher_name.clone(),
+        key_string: cli_args.key_string.clone(),
+        alphabet: cli_args.alphabet.clone(),
+        mode: cli_args.mode,
+        cipher: None,
+        history: vec![],
+    };
+    println!("🔑 Interactive mode, type :help for the commands and :quit to end.");
+    println!("{}", session.describe());
+    if let Err(error) = session.update_cipher() {
+        eprintln!("💣 {}", error);
+    }
+    let mut stdout = io::stdout();
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("{}> ", session.cipher_name);
+        stdout.flush()?;
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        if line == ":quit" {
+            break;
+        }
+        let result: Result<(), std::io::Error> = match line.strip_prefix(':') {
+            Some(command) => {
+                let result: Result<(), std::io::Error> = run_command(&mut session, command);
+                // A `:key` line holds the key in clear, so commands are wiped once run.
+                let mut line_bytes: Vec<u8> = line.into_bytes();
+                zeroize(&mut line_bytes);
+                result
+            }
+            None => process_line(&mut session, line),
+        };
+        if let Err(error) = result {
+            eprintln!("💣 {}", error);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Encrypts or decrypts a line with the current cipher, printing and saving the result.
+fn process_line(session: &mut Session, line: String) -> Result<(), std::io::Error> {
+    let cipher: &dyn Cipher = match &session.cipher {
+        Some(cipher) => cipher.as_ref(),
+        None => return session.update_cipher(),
+    };
+    let processed_line: String = cipher.apply(&line, session.mode);
+    println!("{}", processed_line);
+    session.history.push((line, processed_line, session.mode));
+    Ok(())
+}
+
+/// Runs a command, without the leading `:`, changing the settings of the session.
+fn run_command(session: &mut Session, command: &str) -> Result<(), std::io::Error> {
+    let (name, argument) = match command.split_once(' ') {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (command, ""),
+    };
+    match name {
+        "key" => {
+            session.key_string = SecretString::new(argument.to_string());
+            session.update_cipher()?;
+        }
+        "cipher" => {
+            match ciphers::get_cipher_info(argument) {
+                Some(info) if !info.binary => session.cipher_name = info.name.to_string(),
+                _ => {
+                    let cipher_names: Vec<&str> = ciphers::CIPHERS
+                        .iter()
+                        .filter(|info| !info.binary)
+                        .map(|info| info.name)
+                        .collect();
+                    return Err(invalid_command(&format!(
+                        "⚠️ - Unknown text cipher {}, use one of: {}.",
+                        argument,
+                        cipher_names.join(", ")
+                    )));
+                }
+            }
+            session.update_cipher()?;
+        }
+        "alphabet" => {
+            session.alphabet = new_alphabet(argument)?;
+            session.update_cipher()?;
+        }
+        "encrypt" => session.mode = Mode::Encrypt,
+        "decrypt" => session.mode = Mode::Decrypt,
+        "crack" => crack_line(session, argument)?,
+        "history" => {
+            for (index, (line, processed_line, _)) in session.history.iter().enumerate() {
+                println!("{:>3}: {} → {}", index + 1, line, processed_line);
+            }
+        }
+        "settings" => println!("{}", session.describe()),
+        "help" => print_commands(),
+        _ => {
+            return Err(invalid_command(&format!(
+                "⚠️ - Unknown command :{}, type :help for the commands.",
+                name
+            )));
+        }
+    }
+    if matches!(name, "key" | "cipher" | "alphabet" | "encrypt" | "decrypt") {
+        println!("{}", session.describe());
+    }
+    Ok(())
+}
+
+/// Finds the most likely key of a ciphered text, or of the last ciphered line,
+/// and switches the session to decrypt with it.
+///
+/// The last ciphered line is the result of the last line when it was encrypted,
+/// or the line itself when it was decrypted.
+fn crack_line(session: &mut Session, argument: &str) -> Result<(), std::io::Error> {
+    if session.cipher_name != "caesar" && session.cipher_name != "vigenere" {
+        return Err(invalid_command(
+            "⚠️ - Only the caesar and vigenere ciphers can be cracked.",
+        ));
+    }
+    if session.alphabet.letters() != ALPHABETS[0].letters {
+        return Err(invalid_command(
+            "⚠️ - Only texts with the english alphabet can be cracked.",
+        ));
+    }
+    let ciphered_text: String = if argument.is_empty() {
+        match session.history.last() {
+            Some((_, processed_line, Mode::Encrypt)) => processed_line.clone(),
+            Some((line, _, Mode::Decrypt)) => line.clone(),
+            None => return Err(invalid_command("⚠️ - There is no line to crack yet.")),
+        }
+    } else {
+        argument.to_string()
+    };
+    let key_string: String = if session.cipher_name == "vigenere" {
+        cryptanalysis::crack_vigenere(&ciphered_text).key_string
+    } else {
+        let best_shift: u8 = cryptanalysis::crack_caesar(&ciphered_text).ranking[0].0;
+        cryptanalysis::get_caesar_key_char(best_shift).to_string()
+    };
+    println!("🗝️ Most likely key: {}", key_string);
+    session.key_string = SecretString::new(key_string);
+    session.mode = Mode::Decrypt;
+    session.update_cipher()?;
+    process_line(session, ciphered_text)
+}
+
+/// Prints the commands of the prompt.
+fn print_commands() {
+    println!("📘  Type a line to encrypt or decrypt it, or a command:");
+    for (command, description) in COMMANDS.iter() {
+        println!("      {:<26} {}", command, description);
+    }
+}
+
+/// Creates an error for a command that can not be run.
+fn invalid_command(message: &str) -> std::io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
@@ -0,0 +1,155 @@
+//! Streaming encryption, processing big inputs in chunks instead of loading them whole.
+
+use std::io::{Read, Write};
+
+use crate::ciphers::CharStream;
+
+/// The number of bytes read from the input at once.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+/// The longest UTF-8 sequence that can be split between two chunks.
+const MAX_PENDING_BYTES: usize = 3;
+
+/// Encrypts or decrypts a text from a reader to a writer, one chunk at a time.
+///
+/// The char stream keeps the key position between chunks, so the output is the same
+/// as processing the whole text at once.
+/// A char split between two chunks is kept until the next chunk completes it.
+/// ### Arguments
+/// * `reader` - The input to read, that must be valid UTF-8.
+/// * `writer` - The output to write the processed text.
+/// * `char_stream` - A `CharStream` that encrypts or decrypts every char.
+/// * `chunk_size` - The number of bytes to read at once.
+/// ### Returns
+/// * `Result<u64, std::io::Error>` - The number of bytes read, or an error if reading or
+///   writing failed or the input is not valid UTF-8.
+/// ### Example
+/// ```
+/// use functions::ciphers::{new_cipher, Alphabet, Mode};
+/// use functions::stream::stream_text;
+///
+/// let cipher = new_cipher("vigenere", "lemon", &Alphabet::english()).unwrap();
+/// let mut char_stream = cipher.char_stream(Mode::Encrypt).unwrap();
+/// let mut output: Vec<u8> = vec![];
+/// stream_text(&mut "attack at dawn".as_bytes(), &mut output, &mut char_stream, 4).unwrap();
+/// assert_eq!(output, b"lxfopv ef rnhr");
+/// ```
+pub fn stream_text(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    char_stream: &mut CharStream,
+    chunk_size: usize,
+) -> Result<u64, std::io::Error> {
+    let mut buffer: Vec<u8> = vec![0; MAX_PENDING_BYTES + chunk_size];
+    let mut pending_length: usize = 0;
+    let mut total_bytes: u64 = 0;
+    let mut processed_string: String = String::with_capacity(chunk_size);
+    loop {
+        let read_length: usize = reader.read(&mut buffer[pending_length..])?;
+        if read_length == 0 {
+            break;
+        }
+        total_bytes += read_length as u64;
+        let available_length: usize = pending_length + read_length;
+        let valid_length: usize = match std::str::from_utf8(&buffer[..available_length]) {
+            Ok(_) => available_length,
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(_) => return Err(invalid_utf8()),
+        };
+        let valid_text: &str = std::str::from_utf8(&buffer[..valid_length]).unwrap_or_default();
+        processed_string.clear();
+        processed_string.extend(valid_text.chars().map(&mut *char_stream));
+        writer.write_all(processed_string.as_bytes())?;
+        buffer.copy_within(valid_length..available_length, 0);
+        pending_length = available_length - valid_length;
+    }
+    if pending_length > 0 {
+        return Err(invalid_utf8());
+    }
+    writer.flush()?;
+    Ok(total_bytes)
+}
+
+/// Creates an error for an input that is not valid UTF-8.
+fn invalid_utf8() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "⚠️ - The input is not valid UTF-8 text.",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{new_alphabet, new_cipher, Alphabet, Mode};
+
+    /// A text with chars of two to four bytes, so chunks split them at every size.
+    const SAMPLE_TEXT: &str = "El niño comió 🦀 y pingüinos: ¡ÑAÑA! Über 語 straße.";
+
+    /// Streams a text with a cipher in chunks of a size, returning the output text.
+    fn stream_with(
+        name: &str,
+        key_string: &str,
+        alphabet: &Alphabet,
+        mode: Mode,
+        text: &str,
+        chunk_size: usize,
+    ) -> String {
+        let cipher = new_cipher(name, key_string, alphabet).unwrap();
+        let mut char_stream: CharStream = cipher.char_stream(mode).unwrap();
+        let mut output: Vec<u8> = vec![];
+        let byte_count: u64 = stream_text(
+            &mut text.as_bytes(),
+            &mut output,
+            &mut char_stream,
+            chunk_size,
+        )
+        .unwrap();
+        assert_eq!(byte_count, text.len() as u64);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn streaming_matches_apply_for_every_chunk_size() {
+        let ciphers: [(&str, &str, &str); 6] = [
+            ("caesar", "clave", "spanish"),
+            ("vigenere", "clave", "spanish"),
+            ("atbash", "", "spanish"),
+            ("affine", "5,8", "spanish"),
+            ("rot13", "", "english"),
+            ("caesar", "ñ🦀k", "english"),
+        ];
+        for (name, key_string, alphabet_name) in ciphers {
+            let alphabet: Alphabet = new_alphabet(alphabet_name).unwrap();
+            let cipher = new_cipher(name, key_string, &alphabet).unwrap();
+            for mode in [Mode::Encrypt, Mode::Decrypt] {
+                let expected_text: String = cipher.apply(SAMPLE_TEXT, mode);
+                for chunk_size in 1..=8 {
+                    assert_eq!(
+                        stream_with(name, key_string, &alphabet, mode, SAMPLE_TEXT, chunk_size),
+                        expected_text,
+                        "{} with chunks of {}",
+                        name,
+                        chunk_size
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_truncated_char_at_the_end_is_invalid_data() {
+        let cipher = new_cipher("caesar", "clave", &Alphabet::english()).unwrap();
+        let text_bytes: &[u8] = "añ".as_bytes();
+        for chunk_size in [1, 2, 64] {
+            let mut char_stream: CharStream = cipher.char_stream(Mode::Encrypt).unwrap();
+            let error: std::io::Error = stream_text(
+                &mut &text_bytes[..text_bytes.len() - 1],
+                &mut vec![],
+                &mut char_stream,
+                chunk_size,
+            )
+            .unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Formatting of ciphered texts like classical telegrams, hiding word lengths and punctuation.
+
+use crate::ciphers::Alphabet;
+
+/// The groups of letters written in every line.
+const GROUPS_PER_LINE: usize = 10;
+
+/// A `struct` with the options to format a text around the cipher.
+///
+/// When encrypting, the clean text can be _stripped_ of anything that is not a letter
+/// and turned to _uppercase_, and the ciphered letters can be written in groups of
+/// a _group size_, which also strips the text. When decrypting, _restore_ joins the groups
+/// again; the removed spaces, punctuation and case can not be restored.
+#[derive(Clone, Copy, Default)]
+pub struct TextFormat {
+    /// Whether to remove the chars that are not letters before encrypting.
+    pub strip: bool,
+    /// Whether to turn the letters to uppercase before encrypting.
+    pub uppercase: bool,
+    /// The letters of every group of the ciphered text, or 0 to write it as it is.
+    pub group_size: usize,
+    /// Whether to join the groups before decrypting.
+    pub restore: bool,
+}
+
+impl TextFormat {
+    /// Checks if no option is set, so the text is processed as it is.
+    pub fn is_plain(&self) -> bool {
+        !self.strip && !self.uppercase && self.group_size == 0 && !self.restore
+    }
+}
+
+/// Prepares a clean text to be encrypted, removing the chars that are not letters
+/// and turning it to uppercase, as the format says.
+/// ### Arguments
+/// * `clean_text` - A string slice with the text to encrypt.
+/// * `text_format` - The `TextFormat` with the options.
+/// * `alphabet` - The `Alphabet` whose letters are kept.
+/// ### Returns
+/// * `String` - The text ready to be encrypted.
+/// ### Example
+/// ```
+/// use functions::ciphers::Alphabet;
+/// use functions::telegram::{format_clean_text, TextFormat};
+///
+/// let text_format = TextFormat { strip: true, uppercase: true, ..TextFormat::default() };
+/// let clean_text: String = format_clean_text("Stop. Arrive at 5!", &text_format, &Alphabet::english());
+/// assert_eq!(clean_text, "STOPARRIVEAT");
+/// ```
+pub fn format_clean_text(
+    clean_text: &str,
+    text_format: &TextFormat,
+    alphabet: &Alphabet,
+) -> String {
+    let strip: bool = text_format.strip || text_format.group_size > 0;
+    let mut formatted_text: String = String::with_capacity(clean_text.len());
+    for the_char in clean_text.chars() {
+        if strip && alphabet.get_position(the_char).is_none() {
+            continue;
+        }
+        if text_format.uppercase {
+            formatted_text.extend(the_char.to_uppercase());
+        } else {
+            formatted_text.push(the_char);
+        }
+    }
+    formatted_text
+}
+
+/// Writes a ciphered text in groups of letters separated by spaces, in lines of ten groups.
+/// ### Arguments
+/// * `ciphered_text` - A string slice with the ciphered text, already stripped.
+/// * `group_size` - The letters of every group, usually 5.
+/// ### Returns
+/// * `String` - The grouped text, ending with a line break.
+/// ### Example
+/// ```
+/// use functions::telegram::group_ciphered_text;
+///
+/// assert_eq!(group_ciphered_text("LXFOPVEFRNHR", 5), "LXFOP VEFRN HR\n");
+/// ```
+pub fn group_ciphered_text(ciphered_text: &str, group_size: usize) -> String {
+    let chars: Vec<char> = ciphered_text.chars().collect();
+    let groups: Vec<String> = chars
+        .chunks(group_size.max(1))
+        .map(|group| group.iter().collect())
+        .collect();
+    let mut grouped_text: String = String::with_capacity(ciphered_text.len() * 2);
+    for line in groups.chunks(GROUPS_PER_LINE) {
+        grouped_text.push_str(&line.join(" "));
+        grouped_text.push('\n');
+    }
+    grouped_text
+}
+
+/// Joins the groups of a ciphered text, removing the spaces and line breaks between them,
+/// so the key is aligned as when it was encrypted.
+/// ### Arguments
+/// * `ciphered_text` - A string slice with the grouped text.
+/// ### Returns
+/// * `String` - The ciphered letters together.
+/// ### Example
+/// ```
+/// use functions::telegram::join_groups;
+///
+/// assert_eq!(join_groups("LXFOP VEFRN HR\n"), "LXFOPVEFRNHR");
+/// ```
+pub fn join_groups(ciphered_text: &str) -> String {
+    ciphered_text
+        .chars()
+        .filter(|the_char| !the_char.is_whitespace())
+        .collect()
+}
@@ -0,0 +1,164 @@
+//! Events raised by a [`Blockchain`](crate::Blockchain) when it changes,
+//! so observers can react without reading the console output.
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// A callback subscribed to the [`ChainEvent`] values of a chain.
+type Callback = Box<dyn Fn(&ChainEvent)>;
+
+// * Structs
+
+/// An enum to represent the **changes** that happen on a [`Blockchain`](crate::Blockchain).
+#[derive(Clone, Debug)]
+pub enum ChainEvent {
+    /// A new block was appended at the end of the chain.
+    BlockAppended { index: usize, hash: String },
+    /// A block was not added to the chain, with the reason why.
+    BlockRejected { index: usize, reason: String },
+    /// The chain was replaced by a longer one sharing its first `fork_index` blocks.
+    Reorganised {
+        fork_index: usize,
+        old_length: usize,
+        new_length: usize,
+    },
+    /// A validation of the chain failed, with the reason why.
+    ValidationFailed { reason: String },
+}
+
+/// A struct to hold the **subscribers** of the [`ChainEvent`] values of a chain.
+/// - Subscribers can be callbacks or channel senders.
+#[derive(Default)]
+pub struct EventBus {
+    /// The callbacks invoked for every event.
+    callbacks: Vec<Callback>,
+    /// The channels where every event is sent.
+    senders: Vec<Sender<ChainEvent>>,
+}
+
+// * EventBus implementation
+
+impl EventBus {
+    /// Registers a callback invoked for every [`ChainEvent`].
+    pub fn subscribe(&mut self, callback: impl Fn(&ChainEvent) + 'static) {
+        self.callbacks.push(Box::new(callback));
+    }
+    /// Returns a [`Receiver`] where every [`ChainEvent`] will be sent.
+    pub fn channel(&mut self) -> Receiver<ChainEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.senders.push(sender);
+        receiver
+    }
+    /// Notifies an event to all the subscribers.
+    /// - Channels whose receiver was dropped are silently ignored.
+    pub fn emit(&self, event: ChainEvent) {
+        for callback in &self.callbacks {
+            callback(&event);
+        }
+        for sender in &self.senders {
+            let _ = sender.send(event.clone());
+        }
+    }
+}
+
+/// Implement the [`Display`](fmt::Display) trait for the [`ChainEvent`] enum.
+impl fmt::Display for ChainEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainEvent::BlockAppended { index, hash } => {
+                write!(f, "Block {} appended with hash {}", index, hash)
+            }
+            ChainEvent::BlockRejected { index, reason } => {
+                write!(f, "Block {} rejected: {}", index, reason)
+            }
+            ChainEvent::Reorganised {
+                fork_index,
+                old_length,
+                new_length,
+            } => write!(
+                f,
+                "Reorganised after block {} from {} to {} blocks",
+                fork_index - 1,
+                old_length,
+                new_length
+            ),
+            ChainEvent::ValidationFailed { reason } => write!(f, "Validation failed: {}", reason),
+        }
+    }
+}
+
+/// Implement the [`Debug`](fmt::Debug) trait for the [`EventBus`] struct.
+/// - Only the number of subscribers is shown, as callbacks can not be printed.
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EventBus with {} subscribers",
+            self.callbacks.len() + self.senders.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{Block, Blockchain, Mine, Signature};
+
+    #[test]
+    fn subscribers_and_channels_receive_every_event() {
+        let mut blockchain = Blockchain::new();
+        let callback_events: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
+        let subscribed_events = Rc::clone(&callback_events);
+        blockchain
+            .events
+            .subscribe(move |event| subscribed_events.borrow_mut().push(event.to_string()));
+        let receiver = blockchain.events.channel();
+
+        blockchain.mine("Block 1".to_string());
+        let mut forged_block: Block = blockchain.blocks[1].clone();
+        forged_block.index = 2;
+        forged_block.hash = "forged".to_string();
+        blockchain.add_block(forged_block);
+        let mut fork = blockchain.fork(1);
+        fork.mine("Fork block 2".to_string());
+        fork.mine("Fork block 3".to_string());
+        assert!(blockchain.replace_chain(fork.blocks.clone()));
+        blockchain.blocks[1].data = "Tampered data".to_string();
+        assert!(!blockchain.is_valid());
+
+        let channel_events: Vec<ChainEvent> = receiver.try_iter().collect();
+        assert_eq!(channel_events.len(), 4);
+        assert!(matches!(
+            &channel_events[0],
+            ChainEvent::BlockAppended { index: 1, hash } if *hash == fork.blocks[1].hash
+        ));
+        assert!(matches!(
+            channel_events[1],
+            ChainEvent::BlockRejected { index: 2, .. }
+        ));
+        assert!(matches!(
+            channel_events[2],
+            ChainEvent::Reorganised {
+                fork_index: 2,
+                old_length: 2,
+                new_length: 4
+            }
+        ));
+        assert!(matches!(
+            channel_events[3],
+            ChainEvent::ValidationFailed { .. }
+        ));
+        let displayed_events: Vec<String> = channel_events
+            .iter()
+            .map(|event| event.to_string())
+            .collect();
+        assert_eq!(*callback_events.borrow(), displayed_events);
+        assert_eq!(
+            displayed_events[2],
+            "Reorganised after block 1 from 2 to 4 blocks"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Exports chains of [`Block`] nodes to files: a plain chain file,
+//! a Graphviz DOT diagram and a static HTML block explorer page.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::miner::meets_difficulty;
+use crate::{Block, Signature, DIFFICULTY};
+
+/// The header line written at the top of a chain file.
+const CHAIN_HEADER: &str = "# rs-lab chain v1";
+
+// * Chain files
+
+/// Saves the blocks to a chain file, one block per line with tab separated fields.
+pub fn save_chain(blocks: &[Block], path: &Path) -> io::Result<()> {
+    let mut content = String::from(CHAIN_HEADER);
+    content.push('\n');
+    for block in blocks {
+        content.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            block.index,
+            block.timestamp,
+            block.nonce,
+            block.previous_hash,
+            block.hash,
+            escape_field(&block.data)
+        ));
+    }
+    fs::write(path, content)
+}
+
+/// Loads the blocks from a chain file written by [`save_chain`].
+/// - The blocks are loaded as they are, without validating them.
+pub fn load_chain(path: &Path) -> io::Result<Vec<Block>> {
+    let content = fs::read_to_string(path)?;
+    let mut lines = content.lines();
+    if lines.next() != Some(CHAIN_HEADER) {
+        return Err(invalid_data("⚠️ - Not a chain file."));
+    }
+    let mut blocks: Vec<Block> = vec![];
+    for line in lines.filter(|line| !line.is_empty()) {
+        let fields: Vec<&str> = line.splitn(6, '\t').collect();
+        if fields.len() != 6 {
+            return Err(invalid_data("⚠️ - Malformed chain line."));
+        }
+        blocks.push(Block {
+            index: fields[0]
+                .parse()
+                .map_err(|_| invalid_data("⚠️ - Invalid index."))?,
+            timestamp: fields[1]
+                .parse()
+                .map_err(|_| invalid_data("⚠️ - Invalid timestamp."))?,
+            nonce: fields[2]
+                .parse()
+                .map_err(|_| invalid_data("⚠️ - Invalid nonce."))?,
+            previous_hash: fields[3].to_string(),
+            hash: fields[4].to_string(),
+            data: unescape_field(fields[5]),
+        });
+    }
+    Ok(blocks)
+}
+
+// * DOT diagrams
+
+/// Renders named chains as a Graphviz DOT diagram.
+/// - The first chain is the main one; blocks only found in other chains are drawn dashed as forks.
+/// - Blocks with a wrong hash are filled in red and broken links are drawn as red dashed edges.
+pub fn to_dot(chains: &[(&str, &[Block])]) -> String {
+    let main_hashes: HashSet<&str> = chains
+        .first()
+        .map(|(_, blocks)| blocks.iter().map(|block| block.hash.as_str()).collect())
+        .unwrap_or_default();
+    let mut nodes: HashSet<&str> = HashSet::new();
+    let mut edges: HashSet<(&str, &str)> = HashSet::new();
+    let mut dot = String::from("digraph blockchain {\n    rankdir=LR;\n    node [shape=record];\n");
+    for (name, blocks) in chains {
+        dot.push_str(&format!("    // Chain {}\n", name));
+        for (position, block) in blocks.iter().enumerate() {
+            if nodes.insert(&block.hash) {
+                let mut style = String::new();
+                if block.sign() != block.hash {
+                    style.push_str(", style=filled, fillcolor=red");
+                } else if !main_hashes.contains(block.hash.as_str()) {
+                    style.push_str(", style=dashed, color=gray");
+                }
+                let hash = escape_dot(&block.hash);
+                dot.push_str(&format!(
+                    "    \"{}\" [label=\"{{Block {} | nonce {} | {}}}\"{}];\n",
+                    hash, block.index, block.nonce, hash, style
+                ));
+            }
+            if position == 0 {
+                continue;
+            }
+            let previous_block = &blocks[position - 1];
+            if !edges.insert((&previous_block.hash, &block.hash)) {
+                continue;
+            }
+            let style = if block.previous_hash == previous_block.hash {
+                ""
+            } else {
+                " [color=red, style=dashed, label=\"broken\"]"
+            };
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\"{};\n",
+                escape_dot(&previous_block.hash),
+                escape_dot(&block.hash),
+                style
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// * HTML explorer
+
+/// Generates a static HTML block explorer page from a chain file.
+pub fn export_html(chain_path: &Path, html_path: &Path) -> io::Result<()> {
+    let blocks = load_chain(chain_path)?;
+    let title = chain_path.display().to_string();
+    fs::write(html_path, to_html(&title, &blocks))
+}
+
+/// Renders the blocks as a static HTML page with one row per block.
+/// - Rows of blocks with a wrong hash, no proof of work or a broken link are highlighted.
+fn to_html(title: &str, blocks: &[Block]) -> String {
+    let mut rows = String::new();
+    for (position, block) in blocks.iter().enumerate() {
+        let mut problems: Vec<&str> = vec![];
+        if block.sign() != block.hash {
+            problems.push("wrong hash");
+        } else if !meets_difficulty(&block.hash, DIFFICULTY) {
+            problems.push("no proof of work");
+        }
+        if position > 0 && block.previous_hash != blocks[position - 1].hash {
+            problems.push("broken link");
+        }
+        let (class, status) = if problems.is_empty() {
+            ("valid", "💚 valid".to_string())
+        } else {
+            ("invalid", format!("💔 {}", problems.join(", ")))
+        };
+        rows.push_str(&format!(
+            "      <tr id=\"{hash}\" class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td><code>{hash}</code></td><td><a href=\"#{previous}\"><code>{previous}</code></a></td><td>{}</td><td>{}</td></tr>\n",
+            class,
+            block.index,
+            block.timestamp,
+            block.nonce,
+            escape_html(&block.data),
+            status,
+            hash = escape_html(&block.hash),
+            previous = escape_html(&block.previous_hash),
+        ));
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>Block explorer - {title}</title>
+    <style>
+      body {{ font-family: sans-serif; }}
+      table {{ border-collapse: collapse; }}
+      td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}
+      tr.invalid {{ background: #fdd; }}
+    </style>
+  </head>
+  <body>
+    <h1>🦀 Block explorer</h1>
+    <p>{title}: {count} blocks</p>
+    <table>
+      <tr><th>Index</th><th>Timestamp</th><th>Nonce</th><th>Hash</th><th>Previous hash</th><th>Data</th><th>Status</th></tr>
+{rows}    </table>
+  </body>
+</html>
+"#,
+        title = escape_html(title),
+        count = blocks.len(),
+        rows = rows
+    )
+}
+
+// * Utilities
+
+/// Escapes the characters of a field that would break a chain file line.
+fn escape_field(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Restores a field escaped by [`escape_field`].
+fn unescape_field(field: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = field.chars();
+    while let Some(the_char) = chars.next() {
+        if the_char != '\\' {
+            unescaped.push(the_char);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Escapes the characters with a special meaning in a DOT record label.
+fn escape_dot(text: &str) -> String {
+    let mut escaped = String::new();
+    for the_char in text.chars() {
+        if matches!(the_char, '"' | '\\' | '{' | '}' | '|' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(the_char);
+    }
+    escaped
+}
+
+/// Escapes the characters with a special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Creates an [`io::Error`] for invalid data.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a chain of signed blocks linked to their previous block.
+    fn linked_blocks(data: &[&str]) -> Vec<Block> {
+        let mut blocks: Vec<Block> = vec![];
+        let mut previous_hash = "".to_string();
+        for (index, data) in data.iter().enumerate() {
+            let mut block = Block {
+                index,
+                timestamp: index as u128,
+                data: data.to_string(),
+                previous_hash,
+                nonce: index as u64,
+                hash: "".to_string(),
+            };
+            block.hash = block.sign();
+            previous_hash = block.hash.clone();
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    #[test]
+    fn escape_field_round_trips_separators_and_line_breaks() {
+        for field in [
+            "",
+            "plain",
+            "a\tb",
+            "a\nb",
+            "a\r\nb",
+            "\\t",
+            "ends with \\",
+            "\\\\\r",
+        ] {
+            let escaped = escape_field(field);
+            assert!(!escaped.contains(['\t', '\n', '\r']), "{:?}", escaped);
+            assert_eq!(unescape_field(&escaped), field);
+        }
+    }
+
+    #[test]
+    fn load_chain_restores_the_saved_blocks() {
+        let blocks = linked_blocks(&["Genesis", "tab\there", "line\r\nbreak\n", "back\\slash\\"]);
+        let path = std::env::temp_dir().join(format!("rs-lab-export-{}.chain", std::process::id()));
+        save_chain(&blocks, &path).unwrap();
+        let loaded = load_chain(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.len(), blocks.len());
+        for (loaded_block, block) in loaded.iter().zip(&blocks) {
+            assert_eq!(loaded_block.index, block.index);
+            assert_eq!(loaded_block.timestamp, block.timestamp);
+            assert_eq!(loaded_block.nonce, block.nonce);
+            assert_eq!(loaded_block.previous_hash, block.previous_hash);
+            assert_eq!(loaded_block.hash, block.hash);
+            assert_eq!(loaded_block.data, block.data);
+            assert_eq!(loaded_block.sign(), loaded_block.hash);
+        }
+    }
+
+    #[test]
+    fn dot_and_html_highlight_a_broken_link() {
+        let mut blocks = linked_blocks(&["Genesis", "First", "Second"]);
+        blocks[2].previous_hash = "forged".to_string();
+        blocks[2].hash = blocks[2].sign();
+        let dot = to_dot(&[("main", &blocks)]);
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [color=red, style=dashed, label=\"broken\"];",
+            blocks[1].hash, blocks[2].hash
+        )));
+        assert_eq!(dot.matches("label=\"broken\"").count(), 1);
+        let html = to_html("test", &blocks);
+        let broken_row = html
+            .lines()
+            .find(|line| line.contains(&format!("<tr id=\"{}\"", blocks[2].hash)))
+            .unwrap();
+        assert!(broken_row.contains("class=\"invalid\""));
+        assert!(broken_row.contains("broken link"));
+        assert_eq!(html.matches("broken link").count(), 1);
+    }
+
+    #[test]
+    fn dot_labels_escape_the_record_characters() {
+        let mut blocks = linked_blocks(&["Genesis"]);
+        blocks[0].hash = "a\"b\\c{d}e|f<g>".to_string();
+        let dot = to_dot(&[("main", &blocks)]);
+        let escaped = "a\\\"b\\\\c\\{d\\}e\\|f\\<g\\>";
+        assert_eq!(escape_dot(&blocks[0].hash), escaped);
+        assert!(dot.contains(&format!(
+            "    \"{}\" [label=\"{{Block 0 | nonce 0 | {}}}\"",
+            escaped, escaped
+        )));
+    }
+}
@@ -1,7 +1,25 @@
 use std::collections::hash_map::DefaultHasher;
+use std::env;
 use std::fmt;
+use std::fs;
 use std::hash::{Hash, Hasher};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use events::{ChainEvent, EventBus};
+use miner::{check_proof_of_work, MiningJob};
+use validation::validate_blocks;
+use wallet::Wallet;
+
+mod events;
+mod export;
+mod miner;
+mod validation;
+mod wallet;
+
+/// The number of leading zeros required in the hash of a mined [`Block`].
+const DIFFICULTY: usize = 3;
 
 // * Structs
 
@@ -16,6 +34,8 @@ struct Block {
     data: String,
     /// The hash of the previous block in the [`Blockchain`], used to validate the chain.
     previous_hash: String,
+    /// The number found by mining that makes the hash meet the difficulty.
+    nonce: u64,
     /// A calculated hash of the block, used to self validate.
     hash: String,
 }
@@ -29,6 +49,10 @@ struct Blockchain {
     timestamp: u128,
     /// A calculated hash used to self validate.
     hash: String,
+    /// The number of leading zeros required in the hash of every block.
+    difficulty: usize,
+    /// The subscribers notified of every change.
+    events: EventBus,
 }
 
 // * Trait definitions
@@ -53,12 +77,13 @@ trait Mine {
 /// Implement the [`Hash`] core trait for the [`Block`] struct.
 /// - Overrides the core implementation by using a selection of [`Block`] fields.
 impl Hash for Block {
-    /// Hashes the block. The `index`, `timestamp`, `data`, and `previous_hash` are used.
+    /// Hashes the block. The `index`, `timestamp`, `data`, `previous_hash`, and `nonce` are used.
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.index.hash(state);
         self.timestamp.hash(state);
         self.data.hash(state);
         self.previous_hash.hash(state);
+        self.nonce.hash(state);
     }
 }
 /// Implement the [`Hash`] trait for the [`Blockchain`] struct.
@@ -77,8 +102,8 @@ impl fmt::Debug for Block {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Block {} created at timestamp: {}, signed with hash: {} }}",
-            self.index, self.timestamp, self.hash
+            "Block {} created at timestamp: {}, mined with nonce: {}, signed with hash: {} }}",
+            self.index, self.timestamp, self.nonce, self.hash
         )
     }
 }
@@ -93,6 +118,8 @@ impl Blockchain {
             blocks: vec![],
             timestamp: get_timestamp(),
             hash: "".to_string(),
+            difficulty: DIFFICULTY,
+            events: EventBus::default(),
         };
         blockchain.mine("Genesis block".to_string());
         blockchain.sign();
@@ -102,26 +129,107 @@ impl Blockchain {
     /// Adds a [`Block`] to the [`Blockchain`].
     /// - The [`Blockchain`] hash is updated after adding the block.
     /// - The block is only added to the [`Blockchain`] if it is valid.
+    /// - Emits a [`ChainEvent`] telling if the block was appended or rejected.
     fn add_block(&mut self, block: Block) {
         let block_clone = block.clone();
         self.blocks.push(block);
         self.timestamp = get_timestamp();
         self.hash = self.sign();
-        if self.is_valid() {
-            println!("📘 Added block {:#?}", block_clone);
-        } else {
-            println!("📕 Removing invalid Block {:#?}", block_clone);
-            self.blocks.pop();
+        match self.validate() {
+            Ok(()) => {
+                println!("📘 Added block {:#?}", block_clone);
+                self.events.emit(ChainEvent::BlockAppended {
+                    index: block_clone.index,
+                    hash: block_clone.hash,
+                });
+            }
+            Err(reason) => {
+                println!("💔 {}", reason);
+                println!("📕 Removing invalid Block {:#?}", block_clone);
+                self.blocks.pop();
+                self.events.emit(ChainEvent::BlockRejected {
+                    index: block_clone.index,
+                    reason,
+                });
+            }
         }
     }
+    /// Checks the [`Blockchain`] hash and all its blocks.
+    /// - Returns the reason of the first problem found as an error.
+    fn validate(&self) -> Result<(), String> {
+        let hash = self.sign();
+        if self.hash != hash {
+            return Err(format!(
+                "Blockchain hash {} is not the expected {}",
+                self.hash, hash
+            ));
+        }
+        validate_blocks(&self.blocks)?;
+        check_proof_of_work(&self.blocks, self.difficulty)
+    }
+    /// Creates a new [`Blockchain`] sharing the blocks up to `index` with this one.
+    /// - The fork has no subscribers.
+    fn fork(&self, index: usize) -> Blockchain {
+        let mut fork = Blockchain {
+            blocks: self.blocks[..=index].to_vec(),
+            timestamp: get_timestamp(),
+            hash: "".to_string(),
+            difficulty: self.difficulty,
+            events: EventBus::default(),
+        };
+        fork.hash = fork.sign();
+        println!("🔀 Forked a new blockchain at block {}", index);
+        fork
+    }
+    /// Replaces the blocks of the [`Blockchain`] with a longer valid chain of blocks.
+    /// - Both chains must share at least the genesis block.
+    /// - Emits a [`ChainEvent::Reorganised`] when the blocks are replaced.
+    fn replace_chain(&mut self, blocks: Vec<Block>) -> bool {
+        if blocks.len() <= self.blocks.len() {
+            println!("📕 Ignoring a chain not longer than the current one");
+            return false;
+        }
+        let candidate_result =
+            validate_blocks(&blocks).and_then(|_| check_proof_of_work(&blocks, self.difficulty));
+        if let Err(reason) = candidate_result {
+            println!("💔 {}", reason);
+            self.events.emit(ChainEvent::ValidationFailed { reason });
+            return false;
+        }
+        let fork_index = self
+            .blocks
+            .iter()
+            .zip(&blocks)
+            .take_while(|(current, candidate)| current.hash == candidate.hash)
+            .count();
+        if fork_index == 0 {
+            println!("📕 Ignoring a chain with a different genesis block");
+            return false;
+        }
+        let old_length = self.blocks.len();
+        self.blocks = blocks;
+        self.timestamp = get_timestamp();
+        self.hash = self.sign();
+        println!(
+            "🔀 Reorganised blockchain after block {} to {} blocks",
+            fork_index - 1,
+            self.blocks.len()
+        );
+        self.events.emit(ChainEvent::Reorganised {
+            fork_index,
+            old_length,
+            new_length: self.blocks.len(),
+        });
+        true
+    }
     /// Returns the last block of the [`Blockchain`]
     /// - Being an [`Option`], it returns none when the [`Blockchain`] is empty.
     fn last_block(&self) -> Option<Block> {
         if self.blocks.is_empty() {
-            return None;
+            None
         } else {
             let last_block: Block = self.blocks[self.blocks.len() - 1].clone();
-            return Some(last_block);
+            Some(last_block)
         }
     }
 }
@@ -132,7 +240,7 @@ impl Signature for Block {
     fn sign(&self) -> String {
         let mut hasher = DefaultHasher::new();
         self.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
+        format!("{:016x}", hasher.finish())
     }
     /// Checks if the block is valid by recalculating the hash
     fn is_valid(&self) -> bool {
@@ -159,39 +267,26 @@ impl Signature for Blockchain {
     /// Checks if the [`Blockchain`] is valid.
     /// - The blockchain is valid if all blocks are valid and the hashes are correct.
     /// - Prints a message if the blockchain is not valid explaining why.
+    /// - Emits a [`ChainEvent::ValidationFailed`] if the blockchain is not valid.
     fn is_valid(&self) -> bool {
-        let hash = self.sign();
-        if self.hash != hash {
-            println!(
-                "💔 Blockchain hash {} is not the expected {}",
-                self.hash, hash
-            );
-            return false;
-        }
-        for (index, block) in self.blocks.iter().enumerate() {
-            if !block.is_valid() {
-                println!("💔 Block {} is not valid", index);
-                return false;
+        match self.validate() {
+            Ok(()) => {
+                println!("💚 Blockchain is valid ");
+                true
             }
-            if index > 0 {
-                let previous_block = &self.blocks[index - 1];
-                if block.previous_hash != previous_block.hash {
-                    println!(
-                        "💔 Block {} previous hash {} is not the same as previous block {} hash {}",
-                        block.index, block.previous_hash, previous_block.index, previous_block.hash
-                    );
-                    return false;
-                }
+            Err(reason) => {
+                println!("💔 {}", reason);
+                self.events.emit(ChainEvent::ValidationFailed { reason });
+                false
             }
         }
-        println!("💚 Blockchain is valid ");
-        true
     }
 }
 
 /// Implement the [`Mine`] trait for the [`Blockchain`] struct.
 impl Mine for Blockchain {
     /// Creates a new [`Block`] and adds it to the [`Blockchain`].
+    /// - The nonce of the [`Block`] is searched by a [`MiningJob`] using all the available threads.
     /// - The [`Block`] block is signed and the [`Blockchain`] hash is also updated.
     /// - The method receives a `data` parameter that is the _payload_ of the new block.
     fn mine(&mut self, data: String) {
@@ -202,15 +297,28 @@ impl Mine for Blockchain {
             Some(block) => block.hash.clone(),
             None => "".to_string(),
         };
-        let mut new_block = Block {
+        let new_block = Block {
             index,
             timestamp,
             data,
             previous_hash,
+            nonce: 0,
             hash: "".to_string(),
         };
-        new_block.hash = new_block.sign();
-        self.add_block(new_block);
+        let job = MiningJob::start(new_block, self.difficulty, get_threads());
+        match job.wait() {
+            Some(mined) => {
+                println!(
+                    "⛏️ Mined block {} with {} hashes in {} ms at {:.0} hashes/s",
+                    mined.block.index,
+                    mined.hashes,
+                    mined.elapsed.as_millis(),
+                    mined.hash_rate()
+                );
+                self.add_block(mined.block);
+            }
+            None => println!("🛑 Mining of block {} was cancelled", index),
+        }
     }
 }
 
@@ -222,6 +330,57 @@ fn get_timestamp() -> u128 {
         .as_millis()
 }
 
+/// Get the number of threads available to run in parallel.
+fn get_threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// Starts mining a block too hard to find and cancels it as if a competing block arrived.
+fn cancel_mining(blockchain: &Blockchain) {
+    let last_block = blockchain.last_block().unwrap();
+    let template = Block {
+        index: last_block.index + 1,
+        timestamp: get_timestamp(),
+        data: "Late block".to_string(),
+        previous_hash: last_block.hash,
+        nonce: 0,
+        hash: "".to_string(),
+    };
+    let job = MiningJob::start(template, 16, get_threads());
+    let cancel_handle = job.cancel_handle();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        println!("📨 A competing block arrived, cancelling the mining");
+        cancel_handle.cancel();
+    });
+    match job.wait() {
+        Some(mined) => println!("⛏️ Unexpected mined block {:#?}", mined.block),
+        None => println!("🛑 Mining was cancelled"),
+    }
+}
+
+/// Exports the blockchain and the orphan blocks of a fork to the temporary directory.
+/// - Saves the blockchain to a chain file and generates an HTML explorer page from it.
+/// - Renders both chains as a DOT diagram with the broken links highlighted.
+fn export_chain(blockchain: &Blockchain, orphan_blocks: &[Block]) {
+    let chain_path = env::temp_dir().join("rs-lab.chain");
+    let dot_path = env::temp_dir().join("rs-lab.dot");
+    let html_path = env::temp_dir().join("rs-lab.html");
+    let dot = export::to_dot(&[("main", &blockchain.blocks), ("orphan", orphan_blocks)]);
+    let result = export::save_chain(&blockchain.blocks, &chain_path)
+        .and_then(|_| fs::write(&dot_path, dot))
+        .and_then(|_| export::export_html(&chain_path, &html_path));
+    match result {
+        Ok(()) => println!(
+            "🖼️ Exported {}, {} and {}",
+            chain_path.display(),
+            dot_path.display(),
+            html_path.display()
+        ),
+        Err(error) => println!("💣 Error exporting the blockchain: {}", error),
+    }
+}
+
 /// Utility function to check if a [`Signature`] is valid
 /// - Prints a message with the result.
 fn check_signature(signature: &dyn Signature) -> bool {
@@ -234,22 +393,91 @@ fn check_signature(signature: &dyn Signature) -> bool {
     }
 }
 
+/// Creates a [`Wallet`] with one key, stored and reloaded from an encrypted keystore file.
+fn open_wallet() -> Wallet {
+    let keystore_path = env::temp_dir().join("rs-lab.keystore");
+    let password = "secret";
+    let mut wallet = Wallet::new();
+    wallet.generate_key();
+    if let Err(error) = wallet.save(&keystore_path, password) {
+        println!("💣 Error saving keystore: {}", error);
+        return wallet;
+    }
+    println!("🔐 Saved keystore to {}", keystore_path.display());
+    match Wallet::load(&keystore_path, password) {
+        Ok(loaded_wallet) => loaded_wallet,
+        Err(error) => {
+            println!("💣 Error loading keystore: {}", error);
+            wallet
+        }
+    }
+}
+
+/// Mines a block for every message, signing the payloads with the miner address.
+fn mine_signed(blockchain: &mut Blockchain, wallet: &Wallet, address: &str, messages: &[&str]) {
+    for message in messages {
+        let payload = wallet.sign_payload(address, message).unwrap();
+        blockchain.mine(payload);
+    }
+}
+
 /// Main function to run the [`Blockchain`] as an example of **Rust Traits**.
+/// - Opens a wallet to sign the payloads of the mined blocks.
 /// - Creates a new blockchain and mines some blocks.
+/// - Subscribes to the blockchain events with a callback and a channel.
 /// - Checks the blockchain validity.
+/// - Forks the blockchain and reorganises it with the longer fork.
+/// - Cancels a mining job as if a competing block arrived.
 /// - Changes the data of a block and checks the blockchain validity.
 /// - Changes the data and hash of a block and checks the blockchain validity.
 /// - Prints the blockchain at the end.
+/// - Exports the blockchain to a chain file, a DOT diagram and an HTML explorer page.
+/// - Runs the validation benchmark instead when called with the `bench` argument.
+/// - Generates an HTML explorer page instead when called with `explorer <chain file> <html file>`.
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("bench") => {
+            validation::run_benchmark();
+            return;
+        }
+        Some("explorer") if args.len() == 4 => {
+            match export::export_html(Path::new(&args[2]), Path::new(&args[3])) {
+                Ok(()) => println!("🖼️ Exported {}", args[3]),
+                Err(error) => println!("💣 Error exporting the explorer: {}", error),
+            }
+            return;
+        }
+        _ => {}
+    }
     println!("📖 Hello, rust chains!");
     // Creates a new instance a blockchain struct
     let mut blockchain: Blockchain = Blockchain::new();
-    // Mine some blocks by calling the mine method of the mine trait
-    blockchain.mine("Block 1".to_string());
-    blockchain.mine("Block 2".to_string());
-    blockchain.mine("Block 3".to_string());
+    // Subscribes to the blockchain events
+    blockchain
+        .events
+        .subscribe(|event| println!("📣 Event: {}", event));
+    let event_receiver = blockchain.events.channel();
+    // Opens a wallet to know who mines the blocks
+    let wallet = open_wallet();
+    let miner_address = wallet.addresses()[0].clone();
+    // Mine some signed blocks by calling the mine method of the mine trait
+    mine_signed(
+        &mut blockchain,
+        &wallet,
+        &miner_address,
+        &["Block 1", "Block 2", "Block 3"],
+    );
+    // Check the payloads were signed by the miner
+    for block in &blockchain.blocks[1..] {
+        println!(
+            "🖋️ Block {} payload signed: {}",
+            block.index,
+            wallet::verify_payload(&block.data)
+        );
+    }
     // Check if the blockchain is valid
-    if check_signature(&blockchain) == false {
+    if !check_signature(&blockchain) {
         println!(
             "📕 Unexpected ended with Invalid blockchain {:#?}",
             blockchain
@@ -260,6 +488,18 @@ fn main() {
         }
         return;
     }
+    // Fork the blockchain and replace it with the longer fork
+    let mut fork = blockchain.fork(1);
+    mine_signed(
+        &mut fork,
+        &wallet,
+        &miner_address,
+        &["Fork block 2", "Fork block 3", "Fork block 4"],
+    );
+    let orphan_blocks = blockchain.blocks.clone();
+    blockchain.replace_chain(fork.blocks.clone());
+    // Cancel a mining job that is taking too long
+    cancel_mining(&blockchain);
     // Change the data of a block and check the blockchain validity
     println!("📘 Changing data of block 2");
     blockchain.blocks[2].data = "Changed data on block 2".to_string();
@@ -285,4 +525,9 @@ fn main() {
         "📘 Expected end with Invalid blockchain of {:#?} blocks",
         blockchain.blocks.len()
     );
+    export_chain(&blockchain, &orphan_blocks);
+    println!(
+        "📣 Received {} events on the channel",
+        event_receiver.try_iter().count()
+    );
 }
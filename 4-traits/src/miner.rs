@@ -0,0 +1,199 @@
+//! A **proof of work** miner that searches the nonce of a [`Block`] using several threads.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{Block, Signature};
+
+/// The number of nonces tried by a worker between two checks of the stop flag.
+/// - The hashes are counted locally and added to the shared counter at every check.
+const NONCES_PER_CHECK: u64 = 1_000;
+
+// * Structs
+
+/// A struct to represent a running mining **job**.
+/// - The found block is sent by the workers through a channel.
+pub struct MiningJob {
+    /// The receiver of the block found by any of the workers.
+    receiver: Receiver<Block>,
+    /// A flag shared with the workers to ask them to stop.
+    stop: Arc<AtomicBool>,
+    /// The number of hashes calculated by all the workers.
+    hashes: Arc<AtomicU64>,
+    /// The worker threads searching the nonce.
+    workers: Vec<JoinHandle<()>>,
+    /// The moment the job started.
+    start: Instant,
+}
+
+/// A struct to cancel a [`MiningJob`] from any thread, for example when a competing block arrives.
+#[derive(Clone)]
+pub struct CancelHandle {
+    /// The stop flag shared with the workers of the job.
+    stop: Arc<AtomicBool>,
+}
+
+/// A struct to represent a block found by a [`MiningJob`] and the work done to find it.
+pub struct MinedBlock {
+    /// The block with a nonce and a hash that meets the difficulty.
+    pub block: Block,
+    /// The number of hashes calculated by all the workers.
+    pub hashes: u64,
+    /// The time spent finding the block.
+    pub elapsed: Duration,
+}
+
+// * Implementations
+
+impl MiningJob {
+    /// Starts searching a nonce for the `template` block in `threads` workers.
+    /// - The nonce space is partitioned in one contiguous range per worker.
+    /// - A nonce is valid when the hash starts with `difficulty` zeros.
+    pub fn start(template: Block, difficulty: usize, threads: usize) -> MiningJob {
+        let threads = threads.max(1) as u64;
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let hashes = Arc::new(AtomicU64::new(0));
+        let range_size = u64::MAX / threads;
+        let workers = (0..threads)
+            .map(|worker| {
+                let mut block = template.clone();
+                let sender = sender.clone();
+                let stop = Arc::clone(&stop);
+                let hashes = Arc::clone(&hashes);
+                thread::spawn(move || {
+                    let first_nonce = worker * range_size;
+                    let last_nonce = first_nonce + (range_size - 1);
+                    let mut worker_hashes = 0;
+                    for nonce in first_nonce..=last_nonce {
+                        if nonce % NONCES_PER_CHECK == 0 {
+                            hashes.fetch_add(worker_hashes, Ordering::Relaxed);
+                            worker_hashes = 0;
+                            if stop.load(Ordering::Relaxed) {
+                                break;
+                            }
+                        }
+                        block.nonce = nonce;
+                        block.hash = block.sign();
+                        worker_hashes += 1;
+                        if meets_difficulty(&block.hash, difficulty) {
+                            stop.store(true, Ordering::Relaxed);
+                            let _ = sender.send(block);
+                            break;
+                        }
+                    }
+                    hashes.fetch_add(worker_hashes, Ordering::Relaxed);
+                })
+            })
+            .collect();
+        MiningJob {
+            receiver,
+            stop,
+            hashes,
+            workers,
+            start: Instant::now(),
+        }
+    }
+    /// Returns a [`CancelHandle`] to stop this job from another thread.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle {
+            stop: Arc::clone(&self.stop),
+        }
+    }
+    /// Waits for the workers to finish and returns the found block.
+    /// - Being an [`Option`], it returns none when the job was cancelled.
+    pub fn wait(self) -> Option<MinedBlock> {
+        let found = self.receiver.recv();
+        self.stop.store(true, Ordering::Relaxed);
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+        let block = found.ok()?;
+        Some(MinedBlock {
+            block,
+            hashes: self.hashes.load(Ordering::Relaxed),
+            elapsed: self.start.elapsed(),
+        })
+    }
+}
+
+impl CancelHandle {
+    /// Asks the workers of the job to stop searching.
+    pub fn cancel(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl MinedBlock {
+    /// Returns the number of hashes per second calculated while mining.
+    pub fn hash_rate(&self) -> f64 {
+        self.hashes as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Checks if a hash starts with `difficulty` zeros.
+pub fn meets_difficulty(hash: &str, difficulty: usize) -> bool {
+    hash.len() >= difficulty && hash.bytes().take(difficulty).all(|byte| byte == b'0')
+}
+
+/// Checks that all the blocks have a hash that meets the difficulty.
+/// - Returns the reason of the first problem found as an error.
+pub fn check_proof_of_work(blocks: &[Block], difficulty: usize) -> Result<(), String> {
+    match blocks
+        .iter()
+        .find(|block| !meets_difficulty(&block.hash, difficulty))
+    {
+        Some(block) => Err(format!(
+            "Block {} hash {} does not meet the difficulty of {} zeros",
+            block.index, block.hash, difficulty
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a block to mine, linked to no previous block.
+    fn template_block() -> Block {
+        Block {
+            index: 1,
+            timestamp: 42,
+            data: "Test block".to_string(),
+            previous_hash: "".to_string(),
+            nonce: 0,
+            hash: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn wait_returns_a_block_that_meets_the_difficulty() {
+        let difficulty = 2;
+        let mined = MiningJob::start(template_block(), difficulty, 2)
+            .wait()
+            .unwrap();
+        assert!(meets_difficulty(&mined.block.hash, difficulty));
+        assert_eq!(mined.block.hash, mined.block.sign());
+        assert_eq!(mined.block.data, "Test block");
+        assert!(mined.hashes >= 1);
+        assert_eq!(check_proof_of_work(&[mined.block], difficulty), Ok(()));
+    }
+
+    #[test]
+    fn cancel_stops_a_job_that_can_not_succeed() {
+        // The hashes have 16 hex digits, so 17 zeros are never found.
+        let job = MiningJob::start(template_block(), 17, 2);
+        let cancel_handle = job.cancel_handle();
+        let start = Instant::now();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            cancel_handle.cancel();
+        });
+        assert!(job.wait().is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
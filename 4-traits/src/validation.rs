@@ -0,0 +1,196 @@
+//! Validation of the [`Block`] nodes of a chain, sequential or spread across threads.
+
+use std::thread;
+use std::time::Instant;
+
+use crate::{Block, Signature};
+
+/// The number of blocks from which the validation is spread across threads.
+const PARALLEL_THRESHOLD: usize = 10_000;
+/// The number of blocks of the synthetic chain used by the benchmark.
+const BENCHMARK_BLOCKS: usize = 1_000_000;
+
+/// Checks that all the blocks are valid and linked to their previous block.
+/// - Large chains are validated in parallel, small ones sequentially.
+/// - Returns the reason of the first problem found as an error.
+pub fn validate_blocks(blocks: &[Block]) -> Result<(), String> {
+    if blocks.len() >= PARALLEL_THRESHOLD {
+        validate_parallel(blocks)
+    } else {
+        validate_sequential(blocks)
+    }
+}
+
+/// Checks the blocks one by one, recalculating each hash and its link to the previous block.
+pub fn validate_sequential(blocks: &[Block]) -> Result<(), String> {
+    for (index, block) in blocks.iter().enumerate() {
+        if !block.is_valid() {
+            return Err(format!("Block {} is not valid", index));
+        }
+        if index > 0 {
+            check_link(blocks, index)?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks the blocks recalculating the hashes in scoped threads, one chunk per thread.
+/// - The links between blocks are checked afterwards, as they are cheap to compare.
+/// - Reports the same first problem that [`validate_sequential`] would report.
+pub fn validate_parallel(blocks: &[Block]) -> Result<(), String> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    validate_in_chunks(blocks, threads)
+}
+
+/// Checks the hashes of the blocks split in one chunk for each of the given threads.
+fn validate_in_chunks(blocks: &[Block], threads: usize) -> Result<(), String> {
+    let chunk_size = blocks.len().div_ceil(threads).max(1);
+    let first_invalid: Option<usize> = thread::scope(|scope| {
+        let handles: Vec<_> = blocks
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_index, chunk)| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .position(|block| block.hash != block.sign())
+                        .map(|position| chunk_index * chunk_size + position)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .min()
+    });
+    let first_broken_link: Option<usize> =
+        (1..blocks.len()).find(|&index| check_link(blocks, index).is_err());
+    match (first_invalid, first_broken_link) {
+        (Some(invalid), Some(broken)) if broken < invalid => check_link(blocks, broken),
+        (Some(invalid), _) => Err(format!("Block {} is not valid", invalid)),
+        (None, Some(broken)) => check_link(blocks, broken),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Checks that the block at `index` points to the hash of its previous block.
+fn check_link(blocks: &[Block], index: usize) -> Result<(), String> {
+    let block = &blocks[index];
+    let previous_block = &blocks[index - 1];
+    if block.previous_hash != previous_block.hash {
+        return Err(format!(
+            "Block {} previous hash {} is not the same as previous block {} hash {}",
+            block.index, block.previous_hash, previous_block.index, previous_block.hash
+        ));
+    }
+    Ok(())
+}
+
+/// Creates a chain of signed and linked blocks without validating them one by one.
+fn synthetic_blocks(count: usize) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::with_capacity(count);
+    let mut previous_hash = "".to_string();
+    for index in 0..count {
+        let mut block = Block {
+            index,
+            timestamp: index as u128,
+            data: format!("Synthetic block {}", index),
+            previous_hash,
+            nonce: 0,
+            hash: "".to_string(),
+        };
+        block.hash = block.sign();
+        previous_hash = block.hash.clone();
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Compares the sequential and parallel validation of a synthetic chain.
+/// - Prints the elapsed milliseconds of each strategy and the speedup.
+pub fn run_benchmark() {
+    println!(
+        "⏱️ Creating a synthetic chain of {} blocks",
+        BENCHMARK_BLOCKS
+    );
+    let blocks = synthetic_blocks(BENCHMARK_BLOCKS);
+    let start = Instant::now();
+    let sequential_result = validate_sequential(&blocks);
+    let sequential_ms = start.elapsed().as_millis();
+    println!(
+        "⏱️ Sequential validation: {:?} in {} ms",
+        sequential_result, sequential_ms
+    );
+    let start = Instant::now();
+    let parallel_result = validate_parallel(&blocks);
+    let parallel_ms = start.elapsed().as_millis();
+    println!(
+        "⏱️ Parallel validation: {:?} in {} ms",
+        parallel_result, parallel_ms
+    );
+    println!(
+        "🦀 Parallel speedup: {:.2}x",
+        sequential_ms as f64 / parallel_ms.max(1) as f64
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The blocks of the test chain, split in chunks of 25 blocks.
+    const TEST_BLOCKS: usize = 100;
+    /// The threads of the test validation, so the chunks start at 0, 25, 50 and 75.
+    const TEST_THREADS: usize = 4;
+
+    /// Checks that both strategies report the same error for a tampered chain.
+    fn assert_same_first_error(blocks: &[Block], expected_index: usize) {
+        let sequential_result = validate_sequential(blocks);
+        assert_eq!(validate_in_chunks(blocks, TEST_THREADS), sequential_result);
+        assert_eq!(validate_parallel(blocks), sequential_result);
+        let reason = sequential_result.unwrap_err();
+        assert!(
+            reason.starts_with(&format!("Block {} ", expected_index)),
+            "{}",
+            reason
+        );
+    }
+
+    #[test]
+    fn both_strategies_accept_a_valid_chain() {
+        let blocks = synthetic_blocks(TEST_BLOCKS);
+        assert_eq!(validate_sequential(&blocks), Ok(()));
+        assert_eq!(validate_in_chunks(&blocks, TEST_THREADS), Ok(()));
+        assert_eq!(validate_parallel(&blocks), Ok(()));
+        assert_eq!(validate_in_chunks(&[], TEST_THREADS), Ok(()));
+    }
+
+    #[test]
+    fn both_strategies_report_a_tampered_hash_at_a_chunk_boundary() {
+        for index in [24, 25, TEST_BLOCKS - 1] {
+            let mut blocks = synthetic_blocks(TEST_BLOCKS);
+            blocks[index].hash = "0000000000000000".to_string();
+            assert_same_first_error(&blocks, index);
+        }
+    }
+
+    #[test]
+    fn both_strategies_report_a_broken_link_before_a_later_invalid_block() {
+        for index in [25, 50] {
+            let mut blocks = synthetic_blocks(TEST_BLOCKS);
+            blocks[index].previous_hash = "forged".to_string();
+            blocks[index].hash = blocks[index].sign();
+            blocks[80].data = "Tampered data".to_string();
+            assert_same_first_error(&blocks, index);
+        }
+    }
+
+    #[test]
+    fn both_strategies_report_a_tampered_signature_before_a_later_broken_link() {
+        let mut blocks = synthetic_blocks(TEST_BLOCKS);
+        blocks[25].data = "Tampered data".to_string();
+        blocks[75].previous_hash = "forged".to_string();
+        blocks[75].hash = blocks[75].sign();
+        assert_same_first_error(&blocks, 25);
+    }
+}
@@ -0,0 +1,435 @@
+//! A simple **wallet** to identify who mines or owns the data of a [`Block`](crate::Block).
+//!
+//! > Note: This is a learning sample. The keys are 61 bits long and the hashes
+//! > are 64 bits [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/), so it is **not** safe for real money.
+//! > FNV-1a is used instead of the [`DefaultHasher`](std::collections::hash_map::DefaultHasher)
+//! > because its algorithm is fixed, so saved keystores and addresses stay valid after a Rust upgrade.
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::BuildHasher;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+
+/// The prime modulus of the group used for the keys (the Mersenne prime 2^61 - 1).
+const PRIME: u64 = (1 << 61) - 1;
+/// The base of the group used to derive public keys and signatures.
+const GENERATOR: u64 = 37;
+/// The human-readable prefix of every address.
+const ADDRESS_PREFIX: &str = "rs";
+/// The number of hex characters of the checksum appended to an address.
+const CHECKSUM_LENGTH: usize = 4;
+/// The header line written at the top of a keystore file.
+const KEYSTORE_HEADER: &str = "# rs-lab keystore v3";
+/// The number of hashing rounds used to derive the keystore key from the password.
+const KEYSTORE_ROUNDS: u32 = 10_000;
+/// The separator between the fields of a signed payload.
+const PAYLOAD_SEPARATOR: char = ';';
+/// The initial state of the 64 bits FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// The prime multiplied after every byte by the 64 bits FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+/// The permissions of a keystore file: read and write for the owner only.
+#[cfg(unix)]
+const KEYSTORE_MODE: u32 = 0o600;
+
+// * Structs
+
+/// A struct to represent a **key pair** and the address derived from it.
+#[derive(Clone)]
+struct KeyPair {
+    /// The secret exponent, never written to disk in clear.
+    secret_key: u64,
+    /// The public key, calculated as `GENERATOR ^ secret_key`.
+    public_key: u64,
+    /// The checksummed address derived from the public key.
+    address: String,
+}
+
+/// A struct to represent a **wallet**, a collection of [`KeyPair`] nodes.
+pub struct Wallet {
+    /// The key pairs owned by this wallet.
+    keys: Vec<KeyPair>,
+}
+
+// * Wallet implementation
+
+impl Wallet {
+    /// Creates a new empty wallet.
+    pub fn new() -> Wallet {
+        Wallet { keys: vec![] }
+    }
+    /// Generates a new random [`KeyPair`] and returns its address.
+    pub fn generate_key(&mut self) -> String {
+        let secret_key: u64 = random_u64() % (PRIME - 2) + 1;
+        let key_pair = KeyPair::from_secret(secret_key);
+        let address = key_pair.address.clone();
+        println!("🔑 Generated key for address {}", address);
+        self.keys.push(key_pair);
+        address
+    }
+    /// Returns the addresses of all the keys in the wallet.
+    pub fn addresses(&self) -> Vec<String> {
+        self.keys.iter().map(|key| key.address.clone()).collect()
+    }
+    /// Builds a signed payload ready to be used as the `data` of [`Mine::mine`](crate::Mine::mine).
+    /// - The payload format is `address;public_key;r;s;message`.
+    /// - Being an [`Option`], it returns none when the address is not in the wallet.
+    pub fn sign_payload(&self, address: &str, message: &str) -> Option<String> {
+        let key_pair = self.keys.iter().find(|key| key.address == address)?;
+        let (r, s) = key_pair.sign(message);
+        Some(format!(
+            "{}{sep}{:x}{sep}{:x}{sep}{:x}{sep}{}",
+            key_pair.address,
+            key_pair.public_key,
+            r,
+            s,
+            message,
+            sep = PAYLOAD_SEPARATOR
+        ))
+    }
+    /// Saves the wallet to an encrypted keystore file.
+    /// - Each secret key is encrypted with a key derived from the password and a random salt.
+    /// - On Unix the file is created readable and writable by its owner only.
+    pub fn save(&self, path: &Path, password: &str) -> io::Result<()> {
+        let mut content = String::from(KEYSTORE_HEADER);
+        content.push('\n');
+        for key_pair in &self.keys {
+            let salt = random_u64();
+            let encrypted_secret = key_pair.secret_key ^ derive_keystore_key(password, salt);
+            let check = hash_values(&[
+                password,
+                &salt.to_string(),
+                &key_pair.secret_key.to_string(),
+            ]);
+            content.push_str(&format!(
+                "{} {:x} {:x} {:x} {:x}\n",
+                key_pair.address, key_pair.public_key, salt, encrypted_secret, check
+            ));
+        }
+        write_private(path, &content)
+    }
+    /// Loads a wallet from an encrypted keystore file.
+    /// - Returns an error if the file is malformed or the password is wrong.
+    pub fn load(path: &Path, password: &str) -> io::Result<Wallet> {
+        let content = fs::read_to_string(path)?;
+        let mut lines = content.lines();
+        if lines.next() != Some(KEYSTORE_HEADER) {
+            return Err(invalid_data("⚠️ - Not a keystore file."));
+        }
+        let mut wallet = Wallet::new();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 5 {
+                return Err(invalid_data("⚠️ - Malformed keystore line."));
+            }
+            let salt = parse_hex(fields[2])?;
+            let encrypted_secret = parse_hex(fields[3])?;
+            let check = parse_hex(fields[4])?;
+            let secret_key = encrypted_secret ^ derive_keystore_key(password, salt);
+            if hash_values(&[password, &salt.to_string(), &secret_key.to_string()]) != check {
+                return Err(invalid_data("⚠️ - Wrong keystore password."));
+            }
+            let key_pair = KeyPair::from_secret(secret_key);
+            if key_pair.address != fields[0] || key_pair.public_key != parse_hex(fields[1])? {
+                return Err(invalid_data(
+                    "⚠️ - Keystore key does not match its address.",
+                ));
+            }
+            wallet.keys.push(key_pair);
+        }
+        Ok(wallet)
+    }
+}
+
+// * KeyPair implementation
+
+impl KeyPair {
+    /// Creates a key pair deriving the public key and address from a secret key.
+    fn from_secret(secret_key: u64) -> KeyPair {
+        let public_key = pow_mod(GENERATOR, secret_key, PRIME);
+        KeyPair {
+            secret_key,
+            public_key,
+            address: derive_address(public_key),
+        }
+    }
+    /// Signs a message with a Schnorr signature, returning the `(r, s)` pair.
+    /// - The nonce is derived from the secret key and the message, so signing is deterministic.
+    fn sign(&self, message: &str) -> (u64, u64) {
+        let order = PRIME - 1;
+        let nonce = hash_values(&[&self.secret_key.to_string(), message]) % (order - 1) + 1;
+        let r = pow_mod(GENERATOR, nonce, PRIME);
+        let challenge = get_challenge(r, self.public_key, message);
+        let s =
+            (nonce as u128 + mul_mod(challenge, self.secret_key, order) as u128) % order as u128;
+        (r, s as u64)
+    }
+}
+
+// * Address and payload utilities
+
+/// Derives a human-readable checksummed address from a public key.
+/// - The address is the prefix, 16 hex characters of the key hash, and a 4 hex characters checksum.
+fn derive_address(public_key: u64) -> String {
+    let body = format!(
+        "{}{:016x}",
+        ADDRESS_PREFIX,
+        hash_values(&[&public_key.to_string()])
+    );
+    format!("{}{}", body, get_checksum(&body))
+}
+
+/// Calculates the checksum of an address body.
+/// - The FNV-1a hash barely changes its high bits for a typo near the end of the body,
+///   so it is mixed before taking the first characters.
+fn get_checksum(body: &str) -> String {
+    let checksum = format!("{:016x}", mix_bits(hash_values(&[body])));
+    checksum[..CHECKSUM_LENGTH].to_string()
+}
+
+/// Checks if an address is well formed and its checksum is correct.
+pub fn is_valid_address(address: &str) -> bool {
+    if !address.starts_with(ADDRESS_PREFIX)
+        || address.len() != ADDRESS_PREFIX.len() + 16 + CHECKSUM_LENGTH
+    {
+        return false;
+    }
+    if !address.is_char_boundary(address.len() - CHECKSUM_LENGTH) {
+        return false;
+    }
+    let (body, checksum) = address.split_at(address.len() - CHECKSUM_LENGTH);
+    get_checksum(body) == checksum
+}
+
+/// Verifies a payload built by [`Wallet::sign_payload`].
+/// - The address must match the public key and the signature must match the message.
+pub fn verify_payload(payload: &str) -> bool {
+    let fields: Vec<&str> = payload.splitn(5, PAYLOAD_SEPARATOR).collect();
+    if fields.len() != 5 || !is_valid_address(fields[0]) {
+        return false;
+    }
+    let (public_key, r, s) = match (
+        parse_hex(fields[1]),
+        parse_hex(fields[2]),
+        parse_hex(fields[3]),
+    ) {
+        (Ok(public_key), Ok(r), Ok(s)) => (public_key, r, s),
+        _ => return false,
+    };
+    if derive_address(public_key) != fields[0] {
+        return false;
+    }
+    let challenge = get_challenge(r, public_key, fields[4]);
+    let expected = mul_mod(r, pow_mod(public_key, challenge, PRIME), PRIME);
+    pow_mod(GENERATOR, s, PRIME) == expected
+}
+
+// * Math and hashing utilities
+
+/// Calculates the Schnorr challenge for a signature.
+fn get_challenge(r: u64, public_key: u64, message: &str) -> u64 {
+    hash_values(&[&r.to_string(), &public_key.to_string(), message]) % (PRIME - 1)
+}
+
+/// Derives the key used to encrypt a secret in the keystore by hashing the password repeatedly.
+fn derive_keystore_key(password: &str, salt: u64) -> u64 {
+    let mut key = hash_values(&[password, &salt.to_string()]);
+    for _ in 0..KEYSTORE_ROUNDS {
+        key = hash_values(&[password, &key.to_string()]);
+    }
+    key
+}
+
+/// Multiplies two numbers modulo `modulus` without overflowing.
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+/// Raises `base` to `exponent` modulo `modulus` using square and multiply.
+fn pow_mod(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Hashes a list of values with the 64 bits FNV-1a hash.
+/// - Every value is preceded by its length, so `["ab", "c"]` and `["a", "bc"]` differ.
+fn hash_values(values: &[&str]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for value in values {
+        hash = fnv1a(hash, &(value.len() as u64).to_le_bytes());
+        hash = fnv1a(hash, value.as_bytes());
+    }
+    hash
+}
+
+/// Spreads every bit of a hash over all the others, with the finalizer of SplitMix64.
+fn mix_bits(hash: u64) -> u64 {
+    let hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Feeds some bytes into a 64 bits FNV-1a hash, starting from the `hash` state.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Writes a file that only its owner can read, replacing any previous content.
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(KEYSTORE_MODE);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(KEYSTORE_MODE))?;
+    file.write_all(content.as_bytes())
+}
+
+/// Gets a random number using the randomly seeded [`RandomState`] of the standard library.
+fn random_u64() -> u64 {
+    RandomState::new().hash_one(crate::get_timestamp())
+}
+
+/// Parses a hexadecimal field of a keystore or a payload.
+fn parse_hex(field: &str) -> io::Result<u64> {
+    u64::from_str_radix(field, 16).map_err(|_| invalid_data("⚠️ - Invalid hexadecimal value."))
+}
+
+/// Creates an [`io::Error`] for invalid data.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_the_reference_vectors() {
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    /// Replaces one field of a signed payload.
+    fn replace_field(payload: &str, field_index: usize, value: &str) -> String {
+        let mut fields: Vec<&str> = payload.splitn(5, PAYLOAD_SEPARATOR).collect();
+        fields[field_index] = value;
+        fields.join(&PAYLOAD_SEPARATOR.to_string())
+    }
+
+    #[test]
+    fn signed_payloads_verify_until_tampered() {
+        let mut wallet = Wallet::new();
+        let address = wallet.generate_key();
+        let payload = wallet.sign_payload(&address, "Pay 10; to rs-lab").unwrap();
+        assert!(verify_payload(&payload));
+        assert!(payload.ends_with(";Pay 10; to rs-lab"));
+        assert_eq!(
+            wallet.sign_payload(&address, "Pay 10; to rs-lab"),
+            Some(payload.clone())
+        );
+        assert!(!verify_payload(&replace_field(
+            &payload,
+            4,
+            "Pay 99; to rs-lab"
+        )));
+        let fields: Vec<&str> = payload.split(PAYLOAD_SEPARATOR).collect();
+        let s = parse_hex(fields[3]).unwrap();
+        assert!(!verify_payload(&replace_field(
+            &payload,
+            3,
+            &format!("{:x}", s ^ 1)
+        )));
+        assert!(!verify_payload(&replace_field(&payload, 2, "zz")));
+        assert!(!verify_payload("not a payload"));
+    }
+
+    #[test]
+    fn payloads_do_not_verify_with_another_key() {
+        let mut wallet = Wallet::new();
+        let address = wallet.generate_key();
+        let other_address = wallet.generate_key();
+        let payload = wallet.sign_payload(&address, "Hello").unwrap();
+        let other_payload = wallet.sign_payload(&other_address, "Hello").unwrap();
+        let other_fields: Vec<&str> = other_payload.split(PAYLOAD_SEPARATOR).collect();
+        let forged = replace_field(
+            &replace_field(&payload, 0, other_fields[0]),
+            1,
+            other_fields[1],
+        );
+        assert!(!verify_payload(&forged));
+        assert!(!verify_payload(&replace_field(
+            &payload,
+            1,
+            other_fields[1]
+        )));
+        assert_eq!(wallet.sign_payload("rs0000000000000000abcd", "Hello"), None);
+    }
+
+    #[test]
+    fn mistyped_addresses_fail_the_checksum() {
+        for secret_key in 1..=20 {
+            let address = KeyPair::from_secret(secret_key).address;
+            assert!(is_valid_address(&address));
+            for position in ADDRESS_PREFIX.len()..address.len() {
+                for typo in "0123456789abcdef".chars() {
+                    let mut mistyped = address.clone();
+                    mistyped.replace_range(position..position + 1, &typo.to_string());
+                    if mistyped != address {
+                        assert!(!is_valid_address(&mistyped), "{}", mistyped);
+                    }
+                }
+            }
+        }
+        let address = KeyPair::from_secret(123_456_789).address;
+        assert!(!is_valid_address(&address[1..]));
+        assert!(!is_valid_address(&format!("{}0", address)));
+        assert!(!is_valid_address(&address.replacen(
+            ADDRESS_PREFIX,
+            "xx",
+            1
+        )));
+    }
+
+    #[test]
+    fn saved_keystore_loads_with_the_password_only() {
+        let path = std::env::temp_dir().join(format!("rs-lab-keystore-{}", random_u64()));
+        let mut wallet = Wallet::new();
+        let address = wallet.generate_key();
+        let other_address = wallet.generate_key();
+        wallet.save(&path, "secret").unwrap();
+        let loaded_wallet = Wallet::load(&path, "secret").unwrap();
+        assert_eq!(
+            loaded_wallet.addresses(),
+            vec![address.clone(), other_address]
+        );
+        assert_eq!(
+            loaded_wallet.sign_payload(&address, "Hello"),
+            wallet.sign_payload(&address, "Hello")
+        );
+        let error = Wallet::load(&path, "wrong").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        #[cfg(unix)]
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            KEYSTORE_MODE
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}